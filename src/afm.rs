@@ -0,0 +1,266 @@
+use printpdf::lopdf::Document;
+use printpdf::BuiltinFont;
+
+/*
+   Advance widths from the Adobe AFM files of the base-14 fonts, in 1/1000 em,
+   for every character of WinAnsiEncoding.
+   The oblique Helvetica faces share the upright widths and Courier is fixed at 600.
+*/
+const ASCII_START: u32 = 0x20;
+const LATIN1_START: u32 = 0xa0;
+
+struct Widths {
+    // Space through tilde
+    ascii: [u16; 95],
+    // No-break space through ÿ, which WinAnsiEncoding shares with Latin-1
+    latin1: [u16; 96],
+    // In the order of `WIN_ANSI_EXTRA`
+    extra: [u16; 27],
+}
+
+const HELVETICA: Widths = Widths {
+    #[rustfmt::skip]
+    ascii: [
+        278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+        1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+        667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+        333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+        556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+    ],
+    #[rustfmt::skip]
+    latin1: [
+        278, 333, 556, 556, 556, 556, 260, 556, 333, 737, 370, 556, 584, 333, 737, 333,
+        400, 584, 333, 333, 333, 556, 537, 278, 333, 333, 365, 556, 834, 834, 834, 611,
+        667, 667, 667, 667, 667, 667, 1000, 722, 667, 667, 667, 667, 278, 278, 278, 278,
+        722, 722, 778, 778, 778, 778, 778, 584, 778, 722, 722, 722, 722, 667, 667, 611,
+        556, 556, 556, 556, 556, 556, 889, 500, 556, 556, 556, 556, 278, 278, 278, 278,
+        556, 556, 556, 556, 556, 556, 556, 584, 611, 556, 556, 556, 556, 500, 556, 500,
+    ],
+    #[rustfmt::skip]
+    extra: [
+        556, 222, 556, 333, 1000, 556, 556, 333, 1000, 667, 333, 1000, 611, 222,
+        222, 333, 333, 350, 556, 1000, 333, 1000, 500, 333, 944, 500, 667,
+    ],
+};
+
+const HELVETICA_BOLD: Widths = Widths {
+    #[rustfmt::skip]
+    ascii: [
+        278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+        975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+        667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+        333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+        611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+    ],
+    #[rustfmt::skip]
+    latin1: [
+        278, 333, 556, 556, 556, 556, 280, 556, 333, 737, 370, 556, 584, 333, 737, 333,
+        400, 584, 333, 333, 333, 611, 556, 278, 333, 333, 365, 556, 834, 834, 834, 611,
+        722, 722, 722, 722, 722, 722, 1000, 722, 667, 667, 667, 667, 278, 278, 278, 278,
+        722, 722, 778, 778, 778, 778, 778, 584, 778, 722, 722, 722, 722, 667, 667, 611,
+        556, 556, 556, 556, 556, 556, 889, 556, 556, 556, 556, 556, 278, 278, 278, 278,
+        611, 611, 611, 611, 611, 611, 611, 584, 611, 611, 611, 611, 611, 556, 611, 556,
+    ],
+    #[rustfmt::skip]
+    extra: [
+        556, 278, 556, 500, 1000, 556, 556, 333, 1000, 667, 333, 1000, 611, 278,
+        278, 500, 500, 350, 556, 1000, 333, 1000, 556, 333, 944, 500, 667,
+    ],
+};
+
+const TIMES_ROMAN: Widths = Widths {
+    #[rustfmt::skip]
+    ascii: [
+        250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250, 278,
+        500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564, 564, 444,
+        921, 722, 667, 667, 722, 611, 556, 722, 722, 333, 389, 722, 611, 889, 722, 722,
+        556, 722, 667, 556, 611, 722, 722, 944, 722, 722, 611, 333, 278, 333, 469, 500,
+        333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500, 278, 778, 500, 500,
+        500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, 480, 200, 480, 541,
+    ],
+    #[rustfmt::skip]
+    latin1: [
+        250, 333, 500, 500, 500, 500, 200, 500, 333, 760, 276, 500, 564, 333, 760, 333,
+        400, 564, 300, 300, 333, 500, 453, 250, 333, 300, 310, 500, 750, 750, 750, 444,
+        722, 722, 722, 722, 722, 722, 889, 667, 611, 611, 611, 611, 333, 333, 333, 333,
+        722, 722, 722, 722, 722, 722, 722, 564, 722, 722, 722, 722, 722, 722, 556, 500,
+        444, 444, 444, 444, 444, 444, 667, 444, 444, 444, 444, 444, 278, 278, 278, 278,
+        500, 500, 500, 500, 500, 500, 500, 564, 500, 500, 500, 500, 500, 500, 500, 500,
+    ],
+    #[rustfmt::skip]
+    extra: [
+        500, 333, 500, 444, 1000, 500, 500, 333, 1000, 556, 333, 889, 611, 333,
+        333, 444, 444, 350, 500, 1000, 333, 980, 389, 333, 722, 444, 722,
+    ],
+};
+
+const TIMES_BOLD: Widths = Widths {
+    #[rustfmt::skip]
+    ascii: [
+        250, 333, 555, 500, 500, 1000, 833, 278, 333, 333, 500, 570, 250, 333, 250, 278,
+        500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500,
+        930, 722, 667, 722, 722, 667, 611, 778, 778, 389, 500, 778, 667, 944, 722, 778,
+        611, 778, 722, 556, 667, 722, 722, 1000, 722, 722, 667, 333, 278, 333, 581, 500,
+        333, 500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556, 278, 833, 556, 500,
+        556, 556, 444, 389, 333, 556, 500, 722, 500, 500, 444, 394, 220, 394, 520,
+    ],
+    #[rustfmt::skip]
+    latin1: [
+        250, 333, 500, 500, 500, 500, 220, 500, 333, 747, 300, 500, 570, 333, 747, 333,
+        400, 570, 300, 300, 333, 556, 540, 250, 333, 300, 330, 500, 750, 750, 750, 500,
+        722, 722, 722, 722, 722, 722, 1000, 722, 667, 667, 667, 667, 389, 389, 389, 389,
+        722, 722, 778, 778, 778, 778, 778, 570, 778, 722, 722, 722, 722, 722, 611, 556,
+        500, 500, 500, 500, 500, 500, 722, 444, 444, 444, 444, 444, 278, 278, 278, 278,
+        500, 556, 500, 500, 500, 500, 500, 570, 500, 556, 556, 556, 556, 500, 556, 500,
+    ],
+    #[rustfmt::skip]
+    extra: [
+        500, 333, 500, 500, 1000, 500, 500, 333, 1000, 556, 333, 1000, 667, 333,
+        333, 500, 500, 350, 500, 1000, 333, 1000, 389, 333, 722, 444, 722,
+    ],
+};
+
+const TIMES_ITALIC: Widths = Widths {
+    #[rustfmt::skip]
+    ascii: [
+        250, 333, 420, 500, 500, 833, 778, 214, 333, 333, 500, 675, 250, 333, 250, 278,
+        500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 675, 675, 675, 500,
+        920, 611, 611, 667, 722, 611, 611, 722, 722, 333, 444, 667, 556, 833, 667, 722,
+        611, 722, 611, 500, 556, 722, 611, 833, 611, 556, 556, 389, 278, 389, 422, 500,
+        333, 500, 500, 444, 500, 444, 278, 500, 500, 278, 278, 444, 278, 722, 500, 500,
+        500, 500, 389, 389, 278, 500, 444, 667, 444, 444, 389, 400, 275, 400, 541,
+    ],
+    #[rustfmt::skip]
+    latin1: [
+        250, 389, 500, 500, 500, 500, 275, 500, 333, 760, 276, 500, 675, 333, 760, 333,
+        400, 675, 300, 300, 333, 500, 523, 250, 333, 300, 310, 500, 750, 750, 750, 500,
+        611, 611, 611, 611, 611, 611, 889, 667, 611, 611, 611, 611, 333, 333, 333, 333,
+        722, 667, 722, 722, 722, 722, 722, 675, 722, 722, 722, 722, 722, 556, 611, 500,
+        500, 500, 500, 500, 500, 500, 667, 444, 444, 444, 444, 444, 278, 278, 278, 278,
+        500, 500, 500, 500, 500, 500, 500, 675, 500, 500, 500, 500, 500, 444, 500, 444,
+    ],
+    #[rustfmt::skip]
+    extra: [
+        500, 333, 500, 556, 889, 500, 500, 333, 1000, 500, 333, 944, 556, 333,
+        333, 556, 556, 350, 500, 889, 333, 980, 389, 333, 667, 389, 556,
+    ],
+};
+
+const TIMES_BOLD_ITALIC: Widths = Widths {
+    #[rustfmt::skip]
+    ascii: [
+        250, 389, 555, 500, 500, 833, 778, 278, 333, 333, 500, 570, 250, 333, 250, 278,
+        500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500,
+        832, 667, 667, 667, 722, 667, 667, 722, 778, 389, 500, 667, 611, 889, 722, 722,
+        611, 722, 667, 556, 611, 722, 667, 889, 667, 611, 611, 333, 278, 333, 570, 500,
+        333, 500, 500, 444, 500, 444, 333, 500, 556, 278, 278, 500, 278, 778, 556, 500,
+        500, 500, 389, 389, 278, 556, 444, 667, 500, 444, 389, 348, 220, 348, 570,
+    ],
+    #[rustfmt::skip]
+    latin1: [
+        250, 389, 500, 500, 500, 500, 220, 500, 333, 747, 266, 500, 606, 333, 747, 333,
+        400, 570, 300, 300, 333, 576, 500, 250, 333, 300, 300, 500, 750, 750, 750, 500,
+        667, 667, 667, 667, 667, 667, 944, 667, 667, 667, 667, 667, 389, 389, 389, 389,
+        722, 722, 722, 722, 722, 722, 722, 570, 722, 722, 722, 722, 722, 611, 611, 500,
+        500, 500, 500, 500, 500, 500, 722, 444, 444, 444, 444, 444, 278, 278, 278, 278,
+        500, 556, 500, 500, 500, 500, 500, 570, 500, 556, 556, 556, 556, 444, 500, 444,
+    ],
+    #[rustfmt::skip]
+    extra: [
+        500, 333, 500, 500, 1000, 500, 500, 333, 1000, 556, 333, 944, 611, 333,
+        333, 500, 500, 350, 500, 1000, 333, 1000, 389, 333, 722, 389, 611,
+    ],
+};
+
+/*
+   The characters WinAnsiEncoding places at 0x80 through 0x9F.
+   The AFM files predate the euro sign, so it is given the width of a digit.
+*/
+const WIN_ANSI_EXTRA: [char; 27] = [
+    '€', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', 'Ž', '‘', '’', '“', '”', '•', '–',
+    '—', '˜', '™', 'š', '›', 'œ', 'ž', 'Ÿ',
+];
+
+const COURIER_WIDTH: u16 = 600;
+
+/// Whether `c` has a code in WinAnsiEncoding, the encoding printpdf uses for built-in fonts
+pub fn is_encodable(c: char) -> bool {
+    !Document::encode_text(Some("WinAnsiEncoding"), c.encode_utf8(&mut [0; 4])).is_empty()
+}
+
+/// Advance width of `c` in 1/1000 em, or None when it cannot be written with a built-in font
+pub fn char_width(font: BuiltinFont, c: char) -> Option<u16> {
+    if !is_encodable(c) {
+        return None;
+    }
+
+    let table = match font {
+        BuiltinFont::Courier
+        | BuiltinFont::CourierBold
+        | BuiltinFont::CourierOblique
+        | BuiltinFont::CourierBoldOblique => return Some(COURIER_WIDTH),
+        BuiltinFont::Helvetica | BuiltinFont::HelveticaOblique => &HELVETICA,
+        BuiltinFont::HelveticaBold | BuiltinFont::HelveticaBoldOblique => &HELVETICA_BOLD,
+        BuiltinFont::TimesBold => &TIMES_BOLD,
+        BuiltinFont::TimesItalic => &TIMES_ITALIC,
+        BuiltinFont::TimesBoldItalic => &TIMES_BOLD_ITALIC,
+        _ => &TIMES_ROMAN,
+    };
+
+    table_width(table, c)
+}
+
+fn table_width(table: &Widths, c: char) -> Option<u16> {
+    match c as u32 {
+        code @ 0x20..=0x7e => Some(table.ascii[(code - ASCII_START) as usize]),
+        code @ 0xa0..=0xff => Some(table.latin1[(code - LATIN1_START) as usize]),
+        _ => WIN_ANSI_EXTRA
+            .iter()
+            .position(|&extra| extra == c)
+            .map(|index| table.extra[index]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FACES: [BuiltinFont; 6] = [
+        BuiltinFont::Helvetica,
+        BuiltinFont::HelveticaBold,
+        BuiltinFont::TimesRoman,
+        BuiltinFont::TimesBold,
+        BuiltinFont::TimesItalic,
+        BuiltinFont::TimesBoldItalic,
+    ];
+
+    #[test]
+    fn measures_every_win_ansi_character() {
+        let encodable = (0..0x10000)
+            .filter_map(char::from_u32)
+            .filter(|&c| is_encodable(c));
+
+        for c in encodable {
+            for font in FACES {
+                assert!(char_width(font, c).is_some(), "{:?} in {:?}", c, font);
+            }
+        }
+
+        assert_eq!(char_width(BuiltinFont::Helvetica, '→'), None);
+    }
+
+    #[test]
+    fn uses_the_widths_of_the_afm_files() {
+        let widths = |c| FACES.map(|font| char_width(font, c).unwrap());
+
+        assert_eq!(widths('n'), [556, 611, 500, 556, 500, 556]);
+        assert_eq!(widths('Æ'), [1000, 1000, 889, 1000, 889, 944]);
+        assert_eq!(widths('ß'), [611, 611, 500, 556, 500, 500]);
+        assert_eq!(widths('é'), [556, 556, 444, 444, 444, 444]);
+        assert_eq!(widths('©'), [737, 737, 760, 747, 760, 747]);
+        assert_eq!(widths('…'), [1000, 1000, 1000, 1000, 889, 1000]);
+        assert_eq!(char_width(BuiltinFont::CourierBold, 'Æ'), Some(600));
+    }
+}
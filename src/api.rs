@@ -1,18 +1,42 @@
 #![allow(dead_code)]
 
 use serde::{Deserialize, Serialize};
+
+use chrono::NaiveDate;
+
+use super::dates::{self, DateRange, ResumeDate};
+use super::theme::SectionKind;
+
 // General Sections
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DocumentShape {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub filename: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub header: Option<Header>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub summary: Option<Summary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub employment_history: Option<EmploymentHistory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub projects: Option<Projects>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub contact_details: Option<ContactDetails>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub skillset: Option<SkillSet>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub certifications: Option<Certifications>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub education: Option<Education>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fonts: Option<FontFamily>,
+    // Dated sections whose stored order is kept instead of sorted when writing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub manual_order: Vec<SectionKind>,
 }
 
 #[derive(Debug)]
@@ -26,13 +50,14 @@ pub struct DocumentData {
     pub contact_details: ContactDetails,
     pub skillset: SkillSet,
     pub certifications: Certifications,
+    pub education: Education,
 }
 
 impl Default for DocumentShape {
     fn default() -> Self {
         Self {
             filename: Some("My_Current_Resume.pdf".to_string()),
-            title: Some("Joshua_Diehl_Software_Professional".to_string()),
+            title: None,
             header: None,
             summary: None,
             employment_history: None,
@@ -40,11 +65,43 @@ impl Default for DocumentShape {
             contact_details: None,
             skillset: None,
             certifications: None,
+            education: None,
+            theme: None,
+            fonts: None,
+            manual_order: Vec::new(),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl DocumentShape {
+    /// Employment and education date ranges, with sections named as on the command line
+    pub fn date_ranges(&self) -> Vec<(&'static str, usize, &DateRange)> {
+        let employment = self.employment_history.iter().flatten();
+        let education = self.education.iter().flatten();
+
+        employment
+            .enumerate()
+            .map(|(index, entry)| ("employment", index, &entry.dates_employed))
+            .chain(
+                education
+                    .enumerate()
+                    .map(|(index, entry)| ("education", index, &entry.dates)),
+            )
+            .collect()
+    }
+
+    // Ranges are not validated on load, so one reversed by hand is only caught here
+    pub fn invalid_date_ranges(&self) -> Vec<(&'static str, usize, &DateRange, anyhow::Error)> {
+        self.date_ranges()
+            .into_iter()
+            .filter_map(|(section, index, dates)| {
+                dates.validate().err().map(|e| (section, index, dates, e))
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Header {
     pub name: String,
     pub profession: String,
@@ -67,8 +124,11 @@ pub struct ContactDetails {
 pub struct HistoryEntry {
     pub position: String,
     pub location: String,
-    pub dates_employed: (String, String),
+    pub dates_employed: DateRange,
     pub description: String,
+    // Kept above the date-sorted entries
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
 }
 
 pub type EmploymentHistory = Vec<HistoryEntry>;
@@ -82,8 +142,15 @@ pub type SkillSet = Vec<Skill>;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Certification {
-    pub date_issued: String,
+    #[serde(
+        default,
+        with = "dates::optional",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub date_issued: Option<ResumeDate>,
     pub name: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
 }
 
 pub type Certifications = Vec<Certification>;
@@ -99,14 +166,118 @@ pub type Projects = Vec<Project>;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EducationEntry {
-    pub dates: (String, String),
+    pub dates: DateRange,
     pub name: String,
     pub location: String,
     pub description: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
 }
 
 pub type Education = Vec<EducationEntry>;
 
+// Paths to the TTF/OTF files of a font family; missing variants are simulated
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FontFamily {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regular: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bold: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub italic: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bold_italic: Option<String>,
+}
+
+// Human-readable name used to find a list entry by value instead of by index
+pub trait Labeled {
+    fn label(&self) -> &str;
+}
+
+impl Labeled for HistoryEntry {
+    fn label(&self) -> &str {
+        &self.position
+    }
+}
+
+impl Labeled for Skill {
+    fn label(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Labeled for Certification {
+    fn label(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Labeled for Project {
+    fn label(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Labeled for EducationEntry {
+    fn label(&self) -> &str {
+        &self.name
+    }
+}
+
+// Entries that can be ordered newest first
+pub trait Dated {
+    fn pinned(&self) -> bool;
+    // Compared in descending order: ongoing first, then by latest end, then by latest start
+    fn recency(&self) -> (bool, Option<NaiveDate>, Option<NaiveDate>);
+}
+
+fn range_recency(range: &DateRange) -> (bool, Option<NaiveDate>, Option<NaiveDate>) {
+    let start = range.start.as_ref();
+    // A single date is both the start and the end
+    let end = range.end.as_ref().or(start);
+
+    (
+        range.end == Some(ResumeDate::Present),
+        end.and_then(ResumeDate::last_day),
+        start.and_then(ResumeDate::first_day),
+    )
+}
+
+impl Dated for HistoryEntry {
+    fn pinned(&self) -> bool {
+        self.pinned
+    }
+
+    fn recency(&self) -> (bool, Option<NaiveDate>, Option<NaiveDate>) {
+        range_recency(&self.dates_employed)
+    }
+}
+
+impl Dated for EducationEntry {
+    fn pinned(&self) -> bool {
+        self.pinned
+    }
+
+    fn recency(&self) -> (bool, Option<NaiveDate>, Option<NaiveDate>) {
+        range_recency(&self.dates)
+    }
+}
+
+impl Dated for Certification {
+    fn pinned(&self) -> bool {
+        self.pinned
+    }
+
+    fn recency(&self) -> (bool, Option<NaiveDate>, Option<NaiveDate>) {
+        let issued = self.date_issued.as_ref();
+        (
+            false,
+            issued.and_then(ResumeDate::last_day),
+            issued.and_then(ResumeDate::first_day),
+        )
+    }
+}
+
 struct Font;
 // style?
 // size?
@@ -1,8 +1,27 @@
-use super::api::{Certification, Header, HistoryEntry, Project, Skill, Summary};
-use super::errors::Error;
-use super::file_io::{ConfigFileHandler, FileHandler};
+use super::api::{
+    Certification, ContactDetails, DocumentShape, EducationEntry, FontFamily, Header, HistoryEntry,
+    Project, Skill, Summary,
+};
+use super::dates::{DateRange, ResumeDate};
+use super::editor::Editor;
+use super::entries::Entries;
+use super::errors::PortformError;
+use super::file_io::{ConfigFileHandler, ConfigFormat, FileHandler};
+use super::fonts::{self, BaseFont, FontStyle};
 use super::generate::ResumeWriter;
-use clap::{Arg, ArgMatches, Command};
+use super::html;
+use super::jsonresume::JsonResume;
+use super::lint::Lint;
+use super::metadata::Metadata;
+use super::os;
+use super::profiles::Profiles;
+use super::text::TextFormat;
+use super::theme::{SectionKind, Theme};
+use super::validation;
+use super::wizard::Wizard;
+use anyhow::Context;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::path::{Path, PathBuf};
 
 pub struct Cli;
 
@@ -12,9 +31,37 @@ impl Cli {
             .version("1.0")
             .author("Joshua Newell Diehl <jdiehl2236@gmail.com>")
             .about("PDF Resume Builder")
+            .after_help(PortformError::exit_code_help())
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .arg(
+                Arg::new("config")
+                    .long("config")
+                    .global(true)
+                    .value_name("PATH")
+                    .help("Config file to use instead of the active profile"),
+            )
+            .arg(
+                Arg::new("profile")
+                    .long("profile")
+                    .global(true)
+                    .value_name("NAME")
+                    .help("Profile to use instead of the active one"),
+            )
+            .subcommand(Subcommands::init())
             .subcommand(Subcommands::list())
+            .subcommand(Subcommands::profile())
+            .subcommand(Subcommands::theme())
             .subcommand(Subcommands::set())
             .subcommand(Subcommands::write())
+            .subcommand(Subcommands::edit())
+            .subcommand(Subcommands::remove())
+            .subcommand(Subcommands::relocate())
+            .subcommand(Subcommands::import())
+            .subcommand(Subcommands::export())
+            .subcommand(Subcommands::wizard())
+            .subcommand(Subcommands::sort())
+            .subcommand(Subcommands::lint())
             .arg_required_else_help(true)
             .get_matches()
     }
@@ -23,14 +70,43 @@ impl Cli {
 struct Subcommands;
 
 trait Operator {
+    fn init() -> Command;
     fn list() -> Command;
     fn set() -> Command;
     fn write() -> Command;
+    fn edit() -> Command;
+    fn remove() -> Command;
+    fn relocate() -> Command;
+    fn profile() -> Command;
+    fn theme() -> Command;
+    fn import() -> Command;
+    fn export() -> Command;
+    fn wizard() -> Command;
+    fn sort() -> Command;
+    fn lint() -> Command;
 }
 
+// Sections stored as lists, addressable by index
+const LIST_SECTIONS: [&str; 5] = ["employment", "projects", "skills", "certs", "education"];
+// List sections whose entries carry dates
+const DATED_SECTIONS: [(&str, SectionKind); 3] = [
+    ("employment", SectionKind::Employment),
+    ("education", SectionKind::Education),
+    ("certs", SectionKind::Certifications),
+];
+
 impl Operator for Subcommands {
+    fn init() -> Command {
+        Command::new("init")
+            .about("Create a config file with default values")
+            .args(Arguments::init())
+    }
+
     fn list() -> Command {
-        Command::new("list").subcommand(Command::new("config"))
+        Command::new("list")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .subcommand(Command::new("config"))
     }
 
     fn set() -> Command {
@@ -43,8 +119,13 @@ impl Operator for Subcommands {
         let set_skillset = move |args| Command::new("skills").args(args);
         let set_contact_details = move |args| Command::new("contact").args(args);
         let set_certifications = move |args| Command::new("certs").args(args);
+        let set_education = move |args| Command::new("education").args(args);
+        let set_theme = move |args| Command::new("theme").args(args);
+        let set_fonts = move |args| Command::new("fonts").args(args);
 
         Command::new("set")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
             .subcommand(set_filename(Arguments::filename()))
             .subcommand(set_title(Arguments::title()))
             .subcommand(set_header(Arguments::header()))
@@ -54,10 +135,147 @@ impl Operator for Subcommands {
             .subcommand(set_skillset(Arguments::skillset()))
             .subcommand(set_contact_details(Arguments::contact_details()))
             .subcommand(set_certifications(Arguments::certifications()))
+            .subcommand(set_education(Arguments::education()))
+            .subcommand(set_theme(Arguments::theme()))
+            .subcommand(set_fonts(Arguments::fonts()))
     }
 
     fn write() -> Command {
-        Command::new("write")
+        Command::new("write").args(Arguments::write())
+    }
+
+    fn edit() -> Command {
+        let edit_entry =
+            move |name, args| Command::new(name).arg(Arguments::index("index")).args(args);
+
+        Command::new("edit")
+            .about("Open the config in $VISUAL/$EDITOR, or change fields of a list entry")
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_parser(["json", "yaml", "toml"])
+                    .help("Format to edit in, instead of the config's own"),
+            )
+            .subcommand(
+                edit_entry(
+                    "employment",
+                    Arguments::editable(Arguments::employment_history()),
+                )
+                .arg(Arguments::unpin()),
+            )
+            .subcommand(edit_entry(
+                "projects",
+                Arguments::editable(Arguments::projects()),
+            ))
+            .subcommand(edit_entry(
+                "skills",
+                Arguments::editable(Arguments::skillset()),
+            ))
+            .subcommand(
+                edit_entry("certs", Arguments::editable(Arguments::certifications()))
+                    .arg(Arguments::unpin()),
+            )
+            .subcommand(
+                edit_entry("education", Arguments::editable(Arguments::education()))
+                    .arg(Arguments::unpin()),
+            )
+    }
+
+    fn remove() -> Command {
+        let subcommands = LIST_SECTIONS.map(|name| {
+            Command::new(name).arg(
+                Arg::new("entry")
+                    .required(true)
+                    .help("Index of the entry, or its name"),
+            )
+        });
+
+        Command::new("remove")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .about("Delete a list entry by index or name")
+            .subcommands(subcommands)
+    }
+
+    fn relocate() -> Command {
+        let subcommands = LIST_SECTIONS.map(|name| {
+            Command::new(name)
+                .arg(Arguments::index("from"))
+                .arg(Arguments::index("to"))
+        });
+
+        Command::new("move")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .about("Move a list entry to a new position, keeping dated sections in that order")
+            .subcommands(subcommands)
+    }
+
+    fn profile() -> Command {
+        let name = |id| Arg::new(id).required(true);
+
+        Command::new("profile")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .about("Manage named resume profiles")
+            .subcommand(
+                Command::new("new").arg(name("name")).arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(["json", "yaml", "toml"])
+                        .default_value("json")
+                        .help("File format the profile is stored in"),
+                ),
+            )
+            .subcommand(Command::new("list"))
+            .subcommand(Command::new("use").arg(name("name")))
+            .subcommand(Command::new("copy").arg(name("from")).arg(name("to")))
+            .subcommand(Command::new("delete").arg(name("name")))
+    }
+
+    fn theme() -> Command {
+        Command::new("theme")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .about("Work with visual themes")
+            .subcommand(
+                Command::new("export")
+                    .about("Print a built-in theme as a starting point for a theme file")
+                    .args(Arguments::theme_export()),
+            )
+    }
+
+    fn import() -> Command {
+        Command::new("import")
+            .about("Replace the resume content with one from another format")
+            .args(Arguments::import())
+    }
+
+    fn export() -> Command {
+        Command::new("export")
+            .about("Convert the resume content to another format")
+            .args(Arguments::export())
+    }
+
+    fn sort() -> Command {
+        let subcommands =
+            DATED_SECTIONS.map(|(name, _)| Command::new(name).args(Arguments::sort()));
+
+        Command::new("sort")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .about("Order dated entries newest first, or choose whether writing does so")
+            .subcommands(subcommands)
+    }
+
+    fn wizard() -> Command {
+        Command::new("wizard").about("Build or update the resume interactively, section by section")
+    }
+
+    fn lint() -> Command {
+        Command::new("lint")
+            .about("Check the resume for missing sections, weak wording and other common issues")
+            .args(Arguments::lint())
     }
 }
 
@@ -88,6 +306,110 @@ impl Arguments {
             .unwrap_or(String::with_capacity(7))
     }
 
+    // Get a validated positional index
+    pub fn get_index(args: &ArgMatches, item: &str) -> usize {
+        *args
+            .get_one::<usize>(item)
+            .expect("index arguments are required")
+    }
+
+    pub fn get_date(args: &ArgMatches, item: &str) -> Option<ResumeDate> {
+        args.get_one::<ResumeDate>(item).cloned()
+    }
+
+    // Parsed up front so that a typo is reported before anything is saved
+    pub fn date(name: &'static str) -> Arg {
+        Arg::new(name)
+            .long(name)
+            .value_parser(ResumeDate::parse)
+            .value_name("DATE")
+            .help("e.g. 2021, 2021-03, Mar 2021, 2021-03-15 or present")
+    }
+
+    pub fn pin() -> Arg {
+        Arg::new("pin")
+            .long("pin")
+            .action(ArgAction::SetTrue)
+            .help("Keep the entry above the ones sorted by date")
+    }
+
+    // Only changes the flag when --pin or --unpin was given
+    pub fn apply_pin(args: &ArgMatches, pinned: &mut bool) {
+        if args.get_flag("pin") {
+            *pinned = true;
+        } else if args.get_flag("unpin") {
+            *pinned = false;
+        }
+    }
+
+    pub fn unpin() -> Arg {
+        Arg::new("unpin")
+            .long("unpin")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("pin")
+            .help("Sort the entry by date again")
+    }
+
+    pub fn sort() -> [Arg; 2] {
+        [
+            Arg::new("manual")
+                .long("manual")
+                .action(ArgAction::SetTrue)
+                .help("Keep the stored order when writing instead of sorting by date"),
+            Arg::new("auto")
+                .long("auto")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("manual")
+                .help("Sort by date when writing again"),
+        ]
+    }
+
+    pub fn lint() -> [Arg; 3] {
+        [
+            Arg::new("format")
+                .long("format")
+                .value_parser(["human", "json"])
+                .default_value("human")
+                .help("Output format"),
+            Arg::new("strict")
+                .long("strict")
+                .action(ArgAction::SetTrue)
+                .help("Exit with code 9 when any warning is found, e.g. to fail a CI job"),
+            Arg::new("max-pages")
+                .long("max-pages")
+                .value_parser(clap::value_parser!(u16).range(1..))
+                .default_value("2")
+                .help("Page count above which the resume is reported as too long"),
+        ]
+    }
+
+    pub fn index(name: &'static str) -> Arg {
+        Arg::new(name)
+            .required(true)
+            .value_parser(clap::value_parser!(usize))
+            .help("Zero-based position in the list")
+    }
+
+    // Flags become optional so that only the given fields are changed
+    pub fn editable<const N: usize>(args: [Arg; N]) -> Vec<Arg> {
+        args.into_iter()
+            .map(|arg| {
+                if arg.get_long().is_some() {
+                    arg.required(false)
+                } else {
+                    arg
+                }
+            })
+            .collect()
+    }
+
+    pub fn init() -> [Arg; 1] {
+        [Arg::new("force")
+            .long("force")
+            .action(ArgAction::SetTrue)
+            .help("Overwrite an existing config with the defaults")]
+    }
+
     pub fn filename() -> [Arg; 1] {
         [Arg::new("filename").required(true)]
     }
@@ -112,13 +434,14 @@ impl Arguments {
     }
 
     // New History entry will be appended to vector, overwriting oldest if overflow
-    pub fn employment_history() -> [Arg; 5] {
+    pub fn employment_history() -> [Arg; 6] {
         [
             Arg::new("position").long("position").required(true),
             Arg::new("location").long("location").required(true),
-            Arg::new("start").long("start").required(false),
-            Arg::new("end").long("end").required(false),
+            Arguments::date("start"),
+            Arguments::date("end"),
             Arg::new("description").long("description").required(true),
+            Arguments::pin(),
         ]
     }
 
@@ -143,10 +466,129 @@ impl Arguments {
         ]
     }
 
-    pub fn certifications() -> [Arg; 2] {
+    pub fn education() -> [Arg; 6] {
+        [
+            Arg::new("name").long("name").required(true),
+            Arg::new("location").long("location").required(true),
+            Arguments::date("start"),
+            Arguments::date("end"),
+            Arg::new("description").long("description").required(false),
+            Arguments::pin(),
+        ]
+    }
+
+    pub fn write() -> [Arg; 8] {
+        [
+            Arg::new("format")
+                .long("format")
+                .value_parser(["pdf", "txt", "md", "html"])
+                .default_value("pdf")
+                .help("Output format"),
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("PATH")
+                .help("Write here instead of the configured filename"),
+            Arg::new("width")
+                .long("width")
+                .value_parser(clap::value_parser!(u16).range(20..))
+                .default_value("80")
+                .help("Column to wrap plain text at"),
+            Arg::new("page-numbers")
+                .long("page-numbers")
+                .action(ArgAction::SetTrue)
+                .help("Add a \"Page N of M\" footer to every page"),
+            Arg::new("theme")
+                .long("theme")
+                .value_parser(Theme::BUILTIN)
+                .help("Visual theme for this run, overriding the stored one"),
+            Arg::new("theme-file")
+                .long("theme-file")
+                .value_name("PATH")
+                .conflicts_with("theme")
+                .help("Load the theme from a .toml or .json definition"),
+            Arg::new("font")
+                .long("font")
+                .value_parser(BaseFont::NAMES)
+                .help("Use a built-in PDF font instead of embedding one"),
+            Arg::new("pdfa")
+                .long("pdfa")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("font")
+                .help("Target archival PDF/A-2b: XMP identification, an sRGB output intent and embedded fonts"),
+        ]
+    }
+
+    pub fn theme_export() -> [Arg; 2] {
+        [
+            Arg::new("name").required(true).value_parser(Theme::BUILTIN),
+            Arg::new("format")
+                .long("format")
+                .value_parser(["toml", "json"])
+                .default_value("toml"),
+        ]
+    }
+
+    pub fn theme() -> [Arg; 1] {
+        [Arg::new("theme")
+            .required(true)
+            .value_parser(Theme::BUILTIN)]
+    }
+
+    pub fn fonts() -> [Arg; 5] {
+        let face = |id: &'static str| {
+            Arg::new(id)
+                .long(id)
+                .value_name("PATH")
+                .help("TrueType (.ttf) file for this face")
+        };
+
         [
-            Arg::new("issued").long("issued").required(true),
+            face("regular"),
+            face("bold"),
+            face("italic"),
+            face("bold-italic"),
+            Arg::new("reset")
+                .long("reset")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["regular", "bold", "italic", "bold-italic"])
+                .help("Go back to the built-in font"),
+        ]
+    }
+
+    pub fn import() -> [Arg; 3] {
+        [
+            Arg::new("from")
+                .long("from")
+                .required(true)
+                .value_parser(["jsonresume"]),
+            Arg::new("file").required(true),
+            Arg::new("force")
+                .long("force")
+                .action(ArgAction::SetTrue)
+                .help("Replace the content of an existing config"),
+        ]
+    }
+
+    pub fn export() -> [Arg; 2] {
+        [
+            Arg::new("to")
+                .long("to")
+                .required(true)
+                .value_parser(["jsonresume"]),
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("PATH")
+                .help("File to write instead of stdout"),
+        ]
+    }
+
+    pub fn certifications() -> [Arg; 3] {
+        [
+            Arguments::date("issued").required(true),
             Arg::new("name").long("name").required(true),
+            Arguments::pin(),
         ]
     }
 }
@@ -155,20 +597,50 @@ pub struct CLParser;
 
 pub trait Handler<M, E> {
     fn handle_input() -> Result<(), E>;
+    fn handle_init_command(m: &M) -> Result<(), E>;
     fn handle_list_command(m: &M) -> Result<(), E>;
     fn handle_set_command(m: &M) -> Result<(), E>;
-    fn handle_write_command() -> Result<(), E>;
+    fn handle_write_command(m: &M) -> Result<(), E>;
+    fn handle_edit_command(m: &M) -> Result<(), E>;
+    fn handle_remove_command(m: &M) -> Result<(), E>;
+    fn handle_move_command(m: &M) -> Result<(), E>;
+    fn handle_profile_command(m: &M) -> Result<(), E>;
+    fn handle_theme_command(m: &M) -> Result<(), E>;
+    fn handle_import_command(m: &M) -> Result<(), E>;
+    fn handle_export_command(m: &M) -> Result<(), E>;
+    fn handle_wizard_command(m: &M) -> Result<(), E>;
+    fn handle_sort_command(m: &M) -> Result<(), E>;
+    fn handle_lint_command(m: &M) -> Result<(), E>;
 }
 
 impl Handler<ArgMatches, anyhow::Error> for CLParser {
     /// Handle top-level command
-    fn handle_input() -> Result<(), Error> {
+    fn handle_input() -> Result<(), anyhow::Error> {
         let matches = Cli::matches();
 
+        if let Some(path) = matches.get_one::<String>("config") {
+            os::set_config_override(PathBuf::from(path));
+        }
+
+        if let Some(name) = matches.get_one::<String>("profile") {
+            Profiles::set_override(name.clone());
+        }
+
         match matches.subcommand() {
+            Some(("init", matches)) => Self::handle_init_command(matches)?,
             Some(("list", matches)) => Self::handle_list_command(matches)?,
             Some(("set", matches)) => Self::handle_set_command(matches)?,
-            Some(("write", _)) => Self::handle_write_command()?,
+            Some(("write", matches)) => Self::handle_write_command(matches)?,
+            Some(("edit", matches)) => Self::handle_edit_command(matches)?,
+            Some(("remove", matches)) => Self::handle_remove_command(matches)?,
+            Some(("move", matches)) => Self::handle_move_command(matches)?,
+            Some(("profile", matches)) => Self::handle_profile_command(matches)?,
+            Some(("theme", matches)) => Self::handle_theme_command(matches)?,
+            Some(("import", matches)) => Self::handle_import_command(matches)?,
+            Some(("export", matches)) => Self::handle_export_command(matches)?,
+            Some(("wizard", matches)) => Self::handle_wizard_command(matches)?,
+            Some(("sort", matches)) => Self::handle_sort_command(matches)?,
+            Some(("lint", matches)) => Self::handle_lint_command(matches)?,
             Some((unknown, _)) => eprintln!("Subcommand {:#?} not recognized", unknown),
             None => eprintln!("No matches found for subcommand..."),
         };
@@ -176,7 +648,27 @@ impl Handler<ArgMatches, anyhow::Error> for CLParser {
         Ok(())
     }
 
-    fn handle_list_command(matches: &ArgMatches) -> Result<(), Error> {
+    fn handle_init_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
+        let config_path = os::resolve_config_path()?.0;
+
+        // Refused with exit code 7 so that scripts can tell nothing was written
+        if ConfigFileHandler::exists()? && !matches.get_flag("force") {
+            Err(PortformError::validation(
+                "config",
+                format!(
+                    "{} already exists. Pass --force to replace it",
+                    config_path.display()
+                ),
+            ))?;
+        }
+
+        ConfigFileHandler::write(DocumentShape::default())?;
+        println!("Initialized config at {}", config_path.display());
+
+        Ok(())
+    }
+
+    fn handle_list_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
         match matches.subcommand() {
             Some(("config", _)) => {
                 let config = ConfigFileHandler::read()?;
@@ -188,9 +680,9 @@ impl Handler<ArgMatches, anyhow::Error> for CLParser {
         Ok(())
     }
 
-    fn handle_set_command(matches: &ArgMatches) -> Result<(), Error> {
+    fn handle_set_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
         // Present options mutate config
-        let mut document_config = ConfigFileHandler::read()?;
+        let mut document_config = ConfigFileHandler::read_or_default()?;
 
         match matches.subcommand() {
             Some(("filename", args)) => {
@@ -199,8 +691,45 @@ impl Handler<ArgMatches, anyhow::Error> for CLParser {
             Some(("title", args)) => {
                 document_config.title = Arguments::get_opt(args, "title");
             }
+            Some(("theme", args)) => {
+                document_config.theme = Arguments::get_opt(args, "theme");
+            }
+            Some(("fonts", args)) => {
+                if args.get_flag("reset") {
+                    document_config.fonts = None;
+                } else {
+                    let family = document_config
+                        .fonts
+                        .get_or_insert_with(FontFamily::default);
+                    let faces = [
+                        ("regular", FontStyle::Regular, &mut family.regular),
+                        ("bold", FontStyle::Bold, &mut family.bold),
+                        ("italic", FontStyle::Italic, &mut family.italic),
+                        (
+                            "bold-italic",
+                            FontStyle::BoldItalic,
+                            &mut family.bold_italic,
+                        ),
+                    ];
+
+                    // Stored as absolute paths so `write` works from any directory
+                    for (id, style, slot) in faces {
+                        if let Some(path) = Arguments::get_opt(args, id) {
+                            let path = Path::new(&path).canonicalize().map_err(|e| {
+                                PortformError::FontLoad {
+                                    style: id,
+                                    path: PathBuf::from(&path),
+                                    reason: e.to_string(),
+                                }
+                            })?;
+                            fonts::read_font_file(&path, style)?;
+                            *slot = Some(path.display().to_string());
+                        }
+                    }
+                }
+            }
             Some(("header", args)) => {
-                let header_config = document_config.header.as_mut().unwrap();
+                let header_config = document_config.header.get_or_insert_with(Header::default);
 
                 if let Some(name) = Arguments::get_opt(args, "name") {
                     header_config.name = name;
@@ -218,19 +747,24 @@ impl Handler<ArgMatches, anyhow::Error> for CLParser {
                 document_config.summary = summary;
             }
             Some(("employment", args)) => {
-                let (position, location, start, end, description) = (
+                let (position, location, description) = (
                     Arguments::get(args, "position"),
                     Arguments::get(args, "location"),
-                    Arguments::get_or(args, "start"),
-                    Arguments::get_or(args, "end"),
                     Arguments::get(args, "description"),
                 );
 
+                let dates_employed = DateRange::new(
+                    Arguments::get_date(args, "start"),
+                    Arguments::get_date(args, "end"),
+                )
+                .map_err(|e| PortformError::validation("dates", e))?;
+
                 let history_entry = HistoryEntry {
                     position,
                     location,
-                    dates_employed: (start, end),
+                    dates_employed,
                     description,
+                    pinned: args.get_flag("pin"),
                 };
 
                 if let Some(vector) = document_config.employment_history.as_mut() {
@@ -266,27 +800,31 @@ impl Handler<ArgMatches, anyhow::Error> for CLParser {
                 }
             }
             Some(("contact", args)) => {
-                let mut details = document_config.contact_details.as_mut().unwrap();
+                let details = document_config
+                    .contact_details
+                    .get_or_insert_with(ContactDetails::default);
 
+                // Only the given fields are checked, so older values never block an update
                 if let Some(email) = Arguments::get_opt(args, "email") {
-                    details.email = email;
+                    details.email = validation::email(&email).map_err(PortformError::from)?;
                 };
 
                 if let Some(website) = Arguments::get_opt(args, "website") {
-                    details.website = website;
+                    details.website = validation::website(&website).map_err(PortformError::from)?;
                 };
 
                 if let Some(phone) = Arguments::get_opt(args, "phone") {
-                    details.phone = phone;
+                    details.phone = validation::phone(&phone).map_err(PortformError::from)?;
                 }
                 if let Some(address) = Arguments::get_opt(args, "address") {
-                    details.address = address;
+                    details.address = validation::address(&address).map_err(PortformError::from)?;
                 }
             }
             Some(("certs", args)) => {
                 let cert = Certification {
-                    date_issued: Arguments::get(args, "issued"),
+                    date_issued: Arguments::get_date(args, "issued"),
                     name: Arguments::get(args, "name"),
+                    pinned: args.get_flag("pin"),
                 };
 
                 if let Some(vector) = document_config.certifications.as_mut() {
@@ -297,6 +835,25 @@ impl Handler<ArgMatches, anyhow::Error> for CLParser {
                     document_config.certifications = Some(new_container);
                 }
             }
+            Some(("education", args)) => {
+                let education_entry = EducationEntry {
+                    dates: DateRange::new(
+                        Arguments::get_date(args, "start"),
+                        Arguments::get_date(args, "end"),
+                    )
+                    .map_err(|e| PortformError::validation("dates", e))?,
+                    name: Arguments::get(args, "name"),
+                    location: Arguments::get(args, "location"),
+                    description: Arguments::get(args, "description"),
+                    pinned: args.get_flag("pin"),
+                };
+
+                if let Some(vector) = document_config.education.as_mut() {
+                    vector.push(education_entry);
+                } else {
+                    document_config.education = Some(vec![education_entry]);
+                }
+            }
             Some((unknown, _)) => eprintln!("Subcommand {:#?} not recognized.", unknown),
             None => eprintln!("No matches found for subcommand..."),
         };
@@ -306,44 +863,483 @@ impl Handler<ArgMatches, anyhow::Error> for CLParser {
         Ok(())
     }
 
-    fn handle_write_command() -> Result<(), Error> {
-        let document_data = ConfigFileHandler::read()?;
+    fn handle_write_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
+        let mut document_data = ConfigFileHandler::read()?;
+        Entries::sort_dated_sections(&mut document_data);
 
-        let writer = ResumeWriter::new(document_data.title.clone().unwrap()).load_fonts();
-        let font = writer.get_primary_font();
-        let (doc, pg1, layer1) = writer.doc;
-        let current_layer = doc.get_page(pg1).get_layer(layer1);
-
-        if let Some(header) = document_data.header {
-            ResumeWriter::header_section(
-                current_layer.clone(),
-                font.clone(),
-                header.name,
-                header.profession,
-            );
+        // A theme given on the command line wins over the one stored in the config
+        let theme_name = Arguments::get_opt(matches, "theme").or(document_data.theme.clone());
+        let mut theme = match (Arguments::get_opt(matches, "theme-file"), theme_name) {
+            (Some(path), _) => Theme::from_file(Path::new(&path))
+                .map_err(|e| PortformError::validation("--theme-file", format!("{:#}", e)))?,
+            (None, Some(name)) => Theme::builtin(&name)?,
+            (None, None) => Theme::default(),
+        };
+
+        if let Some(font) = Arguments::get_opt(matches, "font") {
+            theme.font = Some(BaseFont::from_name(&font)?);
         }
 
-        if let Some(contact_section) = document_data.contact_details {
-            ResumeWriter::contact_section(
-                current_layer,
-                font,
-                (
-                    contact_section.email,
-                    contact_section.website,
-                    contact_section.phone,
-                    contact_section.address,
-                ),
-            )
+        let format = Arguments::get_or(matches, "format");
+
+        // Without --output, other formats go next to the PDF with their own extension
+        let output = match Arguments::get_opt(matches, "output") {
+            Some(path) => PathBuf::from(path),
+            None => {
+                let filename = document_data.filename.clone().ok_or_else(|| {
+                    PortformError::validation(
+                        "filename",
+                        "none is set. Run `portform set filename` or pass --output.",
+                    )
+                })?;
+                let filename = PathBuf::from(filename);
+                match format.as_str() {
+                    "pdf" => filename,
+                    extension => filename.with_extension(extension),
+                }
+            }
+        };
+
+        let rendered = match format.as_str() {
+            "txt" => Some(
+                TextFormat::Plain {
+                    columns: matches.get_one::<u16>("width").map_or(80, |w| *w as usize),
+                }
+                .render(&document_data, &theme),
+            ),
+            "md" => Some(TextFormat::Markdown.render(&document_data, &theme)),
+            "html" => Some(html::render(&document_data, &theme)),
+            _ => None,
+        };
+
+        if let Some(rendered) = rendered {
+            std::fs::write(&output, rendered)
+                .map_err(|e| PortformError::output_write(&output, e))?;
+
+            return Ok(());
         }
 
-        let mut buff_writer = super::file_io::ConfigFileHandler::init_write_file(
-            document_data.filename.clone().unwrap(),
-        );
+        // Untitled configs fall back to the author's name for the PDF title
+        let title = document_data
+            .title
+            .clone()
+            .or_else(|| document_data.header.as_ref().map(|h| h.name.clone()))
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| "Resume".to_string());
+
+        let mut writer = ResumeWriter::new(title.clone(), theme)
+            .load_fonts(&document_data.fonts.clone().unwrap_or_default())?
+            .with_page_numbers(matches.get_flag("page-numbers"))
+            .with_metadata(Metadata::from_document(
+                &document_data,
+                &title,
+                matches.get_flag("pdfa"),
+            ));
+
+        if let Some(header) = document_data.header.clone() {
+            writer.header_section(header.name, header.profession);
+        }
+
+        writer.body_sections(&document_data);
+
         /*
            Write sections to file buffer
         */
-        doc.save(&mut buff_writer)?;
+        writer.save(&output)?;
+
+        Ok(())
+    }
+
+    fn handle_edit_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
+        // Without a section, the whole config is edited by hand
+        if matches.subcommand().is_none() {
+            let format =
+                Arguments::get_opt(matches, "format").map(|ext| ConfigFormat::from_extension(&ext));
+
+            return Editor::edit_config(format);
+        }
+
+        let mut document_config = ConfigFileHandler::read()?;
+
+        match matches.subcommand() {
+            Some(("employment", args)) => {
+                let index = Arguments::get_index(args, "index");
+                let entry =
+                    Entries::get_mut(&mut document_config.employment_history, index, "employment")?;
+
+                if let Some(position) = Arguments::get_opt(args, "position") {
+                    entry.position = position;
+                }
+                if let Some(location) = Arguments::get_opt(args, "location") {
+                    entry.location = location;
+                }
+                if args.contains_id("start") || args.contains_id("end") {
+                    entry.dates_employed = DateRange::new(
+                        Arguments::get_date(args, "start").or(entry.dates_employed.start.take()),
+                        Arguments::get_date(args, "end").or(entry.dates_employed.end.take()),
+                    )
+                    .map_err(|e| PortformError::validation("dates", e))?;
+                }
+                if let Some(description) = Arguments::get_opt(args, "description") {
+                    entry.description = description;
+                }
+                Arguments::apply_pin(args, &mut entry.pinned);
+            }
+            Some(("projects", args)) => {
+                let index = Arguments::get_index(args, "index");
+                let entry = Entries::get_mut(&mut document_config.projects, index, "projects")?;
+
+                if let Some(name) = Arguments::get_opt(args, "name") {
+                    entry.name = name;
+                }
+                if let Some(description) = Arguments::get_opt(args, "description") {
+                    entry.description = description;
+                }
+                if let Some(deployment) = Arguments::get_opt(args, "deployment") {
+                    entry.deployment = deployment;
+                }
+            }
+            Some(("skills", args)) => {
+                let index = Arguments::get_index(args, "index");
+                let entry = Entries::get_mut(&mut document_config.skillset, index, "skills")?;
+
+                entry.name = Arguments::get(args, "name");
+            }
+            Some(("certs", args)) => {
+                let index = Arguments::get_index(args, "index");
+                let entry = Entries::get_mut(&mut document_config.certifications, index, "certs")?;
+
+                if let Some(issued) = Arguments::get_date(args, "issued") {
+                    entry.date_issued = Some(issued);
+                }
+                Arguments::apply_pin(args, &mut entry.pinned);
+                if let Some(name) = Arguments::get_opt(args, "name") {
+                    entry.name = name;
+                }
+            }
+            Some(("education", args)) => {
+                let index = Arguments::get_index(args, "index");
+                let entry = Entries::get_mut(&mut document_config.education, index, "education")?;
+
+                if let Some(name) = Arguments::get_opt(args, "name") {
+                    entry.name = name;
+                }
+                if let Some(location) = Arguments::get_opt(args, "location") {
+                    entry.location = location;
+                }
+                if args.contains_id("start") || args.contains_id("end") {
+                    entry.dates = DateRange::new(
+                        Arguments::get_date(args, "start").or(entry.dates.start.take()),
+                        Arguments::get_date(args, "end").or(entry.dates.end.take()),
+                    )
+                    .map_err(|e| PortformError::validation("dates", e))?;
+                }
+                if let Some(description) = Arguments::get_opt(args, "description") {
+                    entry.description = description;
+                }
+                Arguments::apply_pin(args, &mut entry.pinned);
+            }
+            Some((unknown, _)) => eprintln!("Subcommand {:#?} not recognized.", unknown),
+            None => eprintln!("No matches found for subcommand..."),
+        };
+
+        ConfigFileHandler::write(document_config)?;
+
+        Ok(())
+    }
+
+    fn handle_remove_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
+        let mut document_config = ConfigFileHandler::read()?;
+
+        let Some((section, args)) = matches.subcommand() else {
+            eprintln!("No matches found for subcommand...");
+            return Ok(());
+        };
+
+        let target = Arguments::get(args, "entry");
+
+        let removed = match section {
+            "employment" => {
+                Entries::remove(&mut document_config.employment_history, &target, section)?.position
+            }
+            "projects" => Entries::remove(&mut document_config.projects, &target, section)?.name,
+            "skills" => Entries::remove(&mut document_config.skillset, &target, section)?.name,
+            "certs" => Entries::remove(&mut document_config.certifications, &target, section)?.name,
+            "education" => Entries::remove(&mut document_config.education, &target, section)?.name,
+            unknown => {
+                eprintln!("Subcommand {:#?} not recognized.", unknown);
+                return Ok(());
+            }
+        };
+
+        ConfigFileHandler::write(document_config)?;
+        println!("Removed {} entry {:?}", section, removed);
+
+        Ok(())
+    }
+
+    fn handle_move_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
+        let mut document_config = ConfigFileHandler::read()?;
+
+        let Some((section, args)) = matches.subcommand() else {
+            eprintln!("No matches found for subcommand...");
+            return Ok(());
+        };
+
+        let (from, to) = (
+            Arguments::get_index(args, "from"),
+            Arguments::get_index(args, "to"),
+        );
+
+        /*
+           A dated section is written newest first until it is given a manual order,
+           so it is sorted first for the indices to match what the user sees.
+           Writing would sort it again and undo the move, so it keeps its stored order from now on.
+        */
+        let sorted = DATED_SECTIONS
+            .iter()
+            .find(|(name, _)| *name == section)
+            .map(|&(_, kind)| kind)
+            .filter(|kind| !document_config.manual_order.contains(kind));
+
+        if let Some(kind) = sorted {
+            Entries::sort_section(&mut document_config, kind);
+        }
+
+        match section {
+            "employment" => {
+                Entries::relocate(&mut document_config.employment_history, from, to, section)?
+            }
+            "projects" => Entries::relocate(&mut document_config.projects, from, to, section)?,
+            "skills" => Entries::relocate(&mut document_config.skillset, from, to, section)?,
+            "certs" => Entries::relocate(&mut document_config.certifications, from, to, section)?,
+            "education" => Entries::relocate(&mut document_config.education, from, to, section)?,
+            unknown => {
+                eprintln!("Subcommand {:#?} not recognized.", unknown);
+                return Ok(());
+            }
+        };
+
+        if let Some(kind) = sorted {
+            document_config.manual_order.push(kind);
+            println!(
+                "{} entries will now be written in this order. Run `portform sort {} --auto` to sort them by date again",
+                section, section
+            );
+        }
+
+        ConfigFileHandler::write(document_config)?;
+
+        Ok(())
+    }
+
+    fn handle_profile_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
+        match matches.subcommand() {
+            Some(("new", args)) => {
+                let name = Arguments::get(args, "name");
+                Profiles::create(&name, &Arguments::get(args, "format"))?;
+                println!("Created profile {:?}", name);
+            }
+            Some(("list", _)) => {
+                let active = Profiles::active()?;
+
+                for name in Profiles::list()? {
+                    let marker = if name == active { "*" } else { " " };
+                    println!("{} {}", marker, name);
+                }
+            }
+            Some(("use", args)) => {
+                let name = Arguments::get(args, "name");
+                Profiles::set_active(&name)?;
+                println!("Switched to profile {:?}", name);
+            }
+            Some(("copy", args)) => {
+                let (from, to) = (Arguments::get(args, "from"), Arguments::get(args, "to"));
+                Profiles::copy(&from, &to)?;
+                println!("Copied profile {:?} to {:?}", from, to);
+            }
+            Some(("delete", args)) => {
+                let name = Arguments::get(args, "name");
+                Profiles::delete(&name)?;
+                println!("Deleted profile {:?}", name);
+            }
+            Some((unknown, _)) => eprintln!("Subcommand {:#?} not recognized.", unknown),
+            None => eprintln!("No matches found for subcommand..."),
+        };
+
+        Ok(())
+    }
+
+    fn handle_theme_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
+        match matches.subcommand() {
+            Some(("export", args)) => {
+                let theme = Theme::builtin(&Arguments::get(args, "name"))?;
+
+                let output = match Arguments::get(args, "format").as_str() {
+                    "json" => theme.to_json()?,
+                    _ => theme.to_toml()?,
+                };
+
+                println!("{}", output);
+            }
+            Some((unknown, _)) => eprintln!("Subcommand {:#?} not recognized.", unknown),
+            None => eprintln!("No matches found for subcommand..."),
+        };
+
+        Ok(())
+    }
+
+    fn handle_import_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
+        let config_path = os::resolve_config_path()?.0;
+
+        // Refused with exit code 7, like init
+        if ConfigFileHandler::exists()? && !matches.get_flag("force") {
+            Err(PortformError::validation(
+                "config",
+                format!(
+                    "{} already exists. Pass --force to replace its content",
+                    config_path.display()
+                ),
+            ))?;
+        }
+
+        let file = Arguments::get(matches, "file");
+        let json =
+            std::fs::read_to_string(&file).with_context(|| format!("Could not read {}", file))?;
+        let resume: serde_json::Value =
+            serde_json::from_str(&json).with_context(|| format!("{} is not valid JSON", file))?;
+
+        // Output settings such as the theme and fonts are kept
+        let mut config = ConfigFileHandler::read_or_default()?;
+        let (unmapped, unnormalized) = JsonResume::import(resume, &mut config)
+            .with_context(|| format!("Could not import {}", file))?;
+
+        ConfigFileHandler::write(config)?;
+        println!("Imported {} into {}", file, config_path.display());
+        report_unmapped(&unmapped);
+        report_unnormalized(&unnormalized);
+
+        Ok(())
+    }
+
+    fn handle_export_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
+        let mut config = ConfigFileHandler::read()?;
+        // In the order `write` uses
+        Entries::sort_dated_sections(&mut config);
+        let (resume, unmapped) = JsonResume::export(&config);
+        let json = serde_json::to_string_pretty(&resume)?;
+
+        match Arguments::get_opt(matches, "output") {
+            Some(path) => {
+                std::fs::write(&path, json + "\n")
+                    .map_err(|e| PortformError::output_write(&path, e))?;
+                println!("Exported resume to {}", path);
+            }
+            None => println!("{}", json),
+        }
+
+        report_unmapped(&unmapped);
+
+        Ok(())
+    }
+
+    fn handle_wizard_command(_matches: &ArgMatches) -> Result<(), anyhow::Error> {
+        Wizard::run()
+    }
+
+    fn handle_sort_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
+        let mut document_config = ConfigFileHandler::read()?;
+
+        let Some((section, args)) = matches.subcommand() else {
+            eprintln!("No matches found for subcommand...");
+            return Ok(());
+        };
+
+        let Some(&(_, kind)) = DATED_SECTIONS.iter().find(|(name, _)| *name == section) else {
+            eprintln!("Subcommand {:#?} not recognized.", section);
+            return Ok(());
+        };
+
+        let manual_order = &mut document_config.manual_order;
+
+        if args.get_flag("manual") {
+            if !manual_order.contains(&kind) {
+                manual_order.push(kind);
+            }
+            println!("{} entries will be written in their stored order", section);
+        } else if args.get_flag("auto") {
+            manual_order.retain(|k| *k != kind);
+            println!("{} entries will be written newest first", section);
+        } else {
+            Entries::sort_section(&mut document_config, kind);
+            println!("Sorted {} entries newest first", section);
+        }
+
+        ConfigFileHandler::write(document_config)?;
 
         Ok(())
     }
+
+    fn handle_lint_command(matches: &ArgMatches) -> Result<(), anyhow::Error> {
+        let document_data = ConfigFileHandler::read()?;
+
+        // Pages are estimated with the theme `write` would use by default
+        let theme = match &document_data.theme {
+            Some(name) => Theme::builtin(name)?,
+            None => Theme::default(),
+        };
+        let max_pages = matches
+            .get_one::<u16>("max-pages")
+            .map_or(2, |n| *n as usize);
+
+        let report = Lint::run(document_data, theme, max_pages);
+
+        if Arguments::get_or(matches, "format") == "json" {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            for warning in &report.warnings {
+                println!(
+                    "warning[{}] {}: {}",
+                    warning.rule.name(),
+                    warning.location(),
+                    warning.message
+                );
+            }
+
+            match report.warnings.len() {
+                0 => println!("No warnings"),
+                1 => println!("\n1 warning"),
+                count => println!("\n{} warnings", count),
+            }
+        }
+
+        report.check(matches.get_flag("strict"))?;
+
+        Ok(())
+    }
+}
+
+// Conversions are lossy, so list what was left behind on stderr to keep stdout clean
+fn report_unmapped(unmapped: &[String]) {
+    if unmapped.is_empty() {
+        return;
+    }
+
+    eprintln!("These fields could not be mapped and were left out:");
+
+    for path in unmapped {
+        eprintln!("  {}", path);
+    }
+}
+
+fn report_unnormalized(unnormalized: &[String]) {
+    if unnormalized.is_empty() {
+        return;
+    }
+
+    eprintln!("These contact details could not be normalized and were kept as written:");
+
+    for field in unnormalized {
+        eprintln!("  {}", field);
+    }
 }
@@ -1,2 +1,9 @@
 pub const PAGE_X: f64 = 210.0;
 pub const PAGE_Y: f64 = 297.0;
+
+pub const MARGIN_X: f64 = 10.0;
+pub const MARGIN_TOP: f64 = 12.0;
+pub const MARGIN_BOTTOM: f64 = 15.0;
+pub const BODY_LINE_HEIGHT: f64 = 11.0;
+pub const SECTION_GAP: f64 = 6.0;
+pub const ENTRY_GAP: f64 = 2.0;
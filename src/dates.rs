@@ -0,0 +1,450 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::bail;
+use chrono::{Datelike, Local, Month, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::theme::DateStyle;
+
+/*
+   Dates as written on a resume: as precise as the author chose to be.
+   Stored canonically as "2021", "2021-03", "2021-03-15" or "Present".
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResumeDate {
+    Year(i32),
+    Month(i32, u32),
+    Day(NaiveDate),
+    Present,
+    // Kept verbatim from configs written before dates were parsed
+    Text(String),
+}
+
+const PRESENT: &str = "Present";
+const PRESENT_WORDS: [&str; 5] = ["present", "current", "now", "ongoing", "today"];
+
+impl ResumeDate {
+    /// Earliest day the date could mean, with Present as today
+    pub fn first_day(&self) -> Option<NaiveDate> {
+        match self {
+            Self::Year(year) => NaiveDate::from_ymd_opt(*year, 1, 1),
+            Self::Month(year, month) => NaiveDate::from_ymd_opt(*year, *month, 1),
+            Self::Day(day) => Some(*day),
+            Self::Present => Some(Local::now().date_naive()),
+            Self::Text(_) => None,
+        }
+    }
+
+    /// Latest day the date could mean, with Present as today
+    pub fn last_day(&self) -> Option<NaiveDate> {
+        match self {
+            Self::Year(year) => NaiveDate::from_ymd_opt(*year, 12, 31),
+            Self::Month(year, month) => {
+                let (year, month) = if *month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (*year, month + 1)
+                };
+                NaiveDate::from_ymd_opt(year, month, 1)?.pred_opt()
+            }
+            Self::Day(day) => Some(*day),
+            Self::Present => Some(Local::now().date_naive()),
+            Self::Text(_) => None,
+        }
+    }
+
+    pub fn format(&self, style: &DateStyle) -> String {
+        match self {
+            Self::Year(year) => year.to_string(),
+            Self::Month(year, month) => NaiveDate::from_ymd_opt(*year, *month, 1)
+                .map_or_else(|| self.to_string(), |d| d.format(&style.month).to_string()),
+            Self::Day(day) => day.format(&style.day).to_string(),
+            Self::Present => style.present.clone(),
+            Self::Text(text) => text.clone(),
+        }
+    }
+
+    /*
+       Accepts ISO-like forms (2021, 2021-03, 2021/03, 03/2021, 2021-03-15),
+       month names (Mar 2021, March 2021, 15 Mar 2021, Mar 15, 2021) and "present".
+    */
+    pub fn parse(input: &str) -> Result<Self, anyhow::Error> {
+        let text = input.trim();
+
+        if PRESENT_WORDS.contains(&text.to_ascii_lowercase().as_str()) {
+            return Ok(Self::Present);
+        }
+
+        let numbers: Vec<&str> = text.split(['-', '/']).collect();
+        let all_digits = numbers
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+
+        let parsed = if all_digits {
+            match numbers.as_slice() {
+                [year] if year.len() == 4 => Some(Self::Year(year.parse()?)),
+                [year, month] if year.len() == 4 => Self::month(year.parse()?, month.parse()?),
+                [month, year] if year.len() == 4 => Self::month(year.parse()?, month.parse()?),
+                [year, month, day] if year.len() == 4 => {
+                    NaiveDate::from_ymd_opt(year.parse()?, month.parse()?, day.parse()?)
+                        .map(Self::Day)
+                }
+                _ => None,
+            }
+        } else {
+            Self::parse_named(text)
+        };
+
+        match parsed {
+            Some(date) => Ok(date),
+            None => bail!(
+                "Unrecognized date {:?}: use a form like 2021, 2021-03, Mar 2021, 2021-03-15 or Present",
+                input
+            ),
+        }
+    }
+
+    /// Parse `text`, keeping it verbatim when it is not a recognized date
+    pub fn lenient(text: String) -> Self {
+        Self::parse(&text).unwrap_or(Self::Text(text))
+    }
+
+    fn month(year: i32, month: u32) -> Option<Self> {
+        NaiveDate::from_ymd_opt(year, month, 1).map(|_| Self::Month(year, month))
+    }
+
+    // Dates spelled with a month name, ignoring commas and abbreviation dots
+    fn parse_named(text: &str) -> Option<Self> {
+        let words: Vec<&str> = text
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty())
+            .map(|word| word.trim_end_matches('.'))
+            .collect();
+
+        let number = |word: &str, digits: std::ops::RangeInclusive<usize>| {
+            let valid = digits.contains(&word.len()) && word.chars().all(|c| c.is_ascii_digit());
+            valid.then(|| word.parse::<u32>().ok()).flatten()
+        };
+        // chrono knows "Sep" and "September" but not the common "Sept"
+        let month = |word: &str| match word.to_ascii_lowercase().as_str() {
+            "sept" => Some(9),
+            word => word.parse::<Month>().ok().map(|m| m.number_from_month()),
+        };
+
+        match words.as_slice() {
+            [name, year] => Self::month(number(year, 4..=4)? as i32, month(name)?),
+            [name, day, year] | [day, name, year] if month(name).is_some() => {
+                NaiveDate::from_ymd_opt(
+                    number(year, 4..=4)? as i32,
+                    month(name)?,
+                    number(day, 1..=2)?,
+                )
+                .map(Self::Day)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for ResumeDate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for ResumeDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Year(year) => write!(f, "{:04}", year),
+            Self::Month(year, month) => write!(f, "{:04}-{:02}", year, month),
+            Self::Day(day) => write!(f, "{}", day.format("%Y-%m-%d")),
+            Self::Present => f.write_str(PRESENT),
+            Self::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+impl Serialize for ResumeDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+// Anything unparseable is kept as text so that older configs still load
+impl<'de> Deserialize<'de> for ResumeDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::lenient(String::deserialize(deserializer)?))
+    }
+}
+
+// A start and end date, either of which may be left out
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub start: Option<ResumeDate>,
+    pub end: Option<ResumeDate>,
+}
+
+impl DateRange {
+    pub fn new(start: Option<ResumeDate>, end: Option<ResumeDate>) -> Result<Self, anyhow::Error> {
+        let range = Self { start, end };
+        range.validate()?;
+        Ok(range)
+    }
+
+    // Dates of differing precision overlap, so 2021 to Mar 2021 is allowed
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        if let (Some(start), Some(end)) = (&self.start, &self.end) {
+            if start == &ResumeDate::Present && end != &ResumeDate::Present {
+                bail!("Start date cannot be Present when an end date is given");
+            }
+
+            if let (Some(earliest), Some(latest)) = (start.first_day(), end.last_day()) {
+                if latest < earliest {
+                    bail!("End date {} is before start date {}", end, start);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Whole months covered, counting both the first and last month. None without an end date
+    pub fn months(&self) -> Option<u32> {
+        let end = self.end.as_ref()?.last_day()?;
+        let start = self.start.as_ref()?.first_day()?;
+
+        let months = (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+        u32::try_from(months + 1).ok()
+    }
+
+    /// e.g. "Mar 2021 – Present · 2 yrs 4 mos", or None when both dates are missing
+    pub fn format(&self, style: &DateStyle) -> Option<String> {
+        let start = self.start.as_ref().map(|d| d.format(style));
+        let end = self.end.as_ref().map(|d| d.format(style));
+
+        let range = match (start, end) {
+            (None, None) => return None,
+            (Some(date), None) | (None, Some(date)) => date,
+            (Some(start), Some(end)) if start == end => start,
+            (Some(start), Some(end)) => format!("{}{}{}", start, style.separator, end),
+        };
+
+        match self.months().filter(|_| style.durations) {
+            Some(months) => Some(format!("{} · {}", range, duration(months))),
+            None => Some(range),
+        }
+    }
+}
+
+impl Serialize for DateRange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let text =
+            |date: &Option<ResumeDate>| date.as_ref().map_or(String::new(), |d| d.to_string());
+        (text(&self.start), text(&self.end)).serialize(serializer)
+    }
+}
+
+/*
+   Stored as a [start, end] pair with "" for a missing date, as before dates were parsed.
+   Not validated here: a reversed range must not stop the config from loading,
+   or it could never be fixed. Commands that change dates validate, and lint reports the rest.
+*/
+impl<'de> Deserialize<'de> for DateRange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (start, end) = <(String, String)>::deserialize(deserializer)?;
+        let date = |text: String| {
+            Some(text)
+                .filter(|t| !t.trim().is_empty())
+                .map(ResumeDate::lenient)
+        };
+
+        Ok(Self {
+            start: date(start),
+            end: date(end),
+        })
+    }
+}
+
+// e.g. "1 yr", "4 mos", "2 yrs 4 mos"
+pub fn duration(months: u32) -> String {
+    let plural = |n: u32, unit: &str| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" });
+    let (years, months) = (months / 12, months % 12);
+
+    match (years, months) {
+        (0, months) => plural(months.max(1), "mo"),
+        (years, 0) => plural(years, "yr"),
+        (years, months) => format!("{} {}", plural(years, "yr"), plural(months, "mo")),
+    }
+}
+
+// Serde adapter for a single optional date stored as a string, "" when missing
+pub mod optional {
+    use super::ResumeDate;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        date: &Option<ResumeDate>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match date {
+            Some(date) => serializer.collect_str(date),
+            None => serializer.serialize_str(""),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<ResumeDate>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+
+        Ok(Some(text)
+            .filter(|t| !t.trim().is_empty())
+            .map(ResumeDate::lenient))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> ResumeDate {
+        ResumeDate::parse(text).unwrap()
+    }
+
+    fn range(start: &str, end: &str) -> DateRange {
+        DateRange::new(Some(parse(start)), Some(parse(end))).unwrap()
+    }
+
+    #[test]
+    fn parses_numeric_forms() {
+        assert_eq!(parse("2021"), ResumeDate::Year(2021));
+        assert_eq!(parse("2021-03"), ResumeDate::Month(2021, 3));
+        assert_eq!(parse("2021/3"), ResumeDate::Month(2021, 3));
+        assert_eq!(parse("03/2021"), ResumeDate::Month(2021, 3));
+        assert_eq!(
+            parse(" 2021-03-15 "),
+            ResumeDate::Day(NaiveDate::from_ymd_opt(2021, 3, 15).unwrap())
+        );
+    }
+
+    #[test]
+    fn parses_month_names() {
+        let ides = ResumeDate::Day(NaiveDate::from_ymd_opt(2021, 3, 15).unwrap());
+
+        assert_eq!(parse("Mar 2021"), ResumeDate::Month(2021, 3));
+        assert_eq!(parse("march 2021"), ResumeDate::Month(2021, 3));
+        assert_eq!(parse("Sept. 2019"), ResumeDate::Month(2019, 9));
+        assert_eq!(parse("15 Mar 2021"), ides);
+        assert_eq!(parse("Mar 15, 2021"), ides);
+    }
+
+    #[test]
+    fn parses_present_words() {
+        for word in ["present", "Present", "current", "NOW", "ongoing", "today"] {
+            assert_eq!(parse(word), ResumeDate::Present, "{}", word);
+        }
+    }
+
+    #[test]
+    fn rejects_free_text_and_impossible_dates() {
+        for text in [
+            "",
+            "soon",
+            "Spring '20",
+            "21-03",
+            "2021-13",
+            "2021-02-30",
+            "Mar 20",
+            "Foo 2021",
+        ] {
+            assert!(ResumeDate::parse(text).is_err(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn keeps_unparseable_text_when_loading() {
+        let date: ResumeDate = serde_json::from_str("\"Spring '20\"").unwrap();
+        assert_eq!(date, ResumeDate::Text("Spring '20".to_string()));
+        assert_eq!(date.to_string(), "Spring '20");
+
+        assert_eq!(ResumeDate::lenient("2021-03".to_string()), parse("2021-03"));
+        assert_eq!(
+            ResumeDate::lenient("soon".to_string()),
+            ResumeDate::Text("soon".to_string())
+        );
+    }
+
+    #[test]
+    fn displays_canonically() {
+        for text in ["2021", "2021-03", "2021-03-15", "Present"] {
+            assert_eq!(parse(text).to_string(), text);
+        }
+        assert_eq!(parse("Mar 15, 2021").to_string(), "2021-03-15");
+    }
+
+    #[test]
+    fn validates_range_order() {
+        assert!(DateRange::new(Some(parse("2021")), Some(parse("2019"))).is_err());
+        assert!(DateRange::new(Some(parse("present")), Some(parse("2019"))).is_err());
+        assert!(DateRange::new(Some(parse("2021-05")), Some(parse("2021-03"))).is_err());
+        // Overlapping precisions are allowed
+        assert!(DateRange::new(Some(parse("2021")), Some(parse("2021-03"))).is_ok());
+        assert!(DateRange::new(Some(parse("2019")), Some(parse("present"))).is_ok());
+        assert!(DateRange::new(None, Some(parse("2019"))).is_ok());
+        // Free text cannot be compared, so it is let through
+        let text = Some(ResumeDate::Text("someday".to_string()));
+        assert!(DateRange::new(Some(parse("2021")), text).is_ok());
+    }
+
+    #[test]
+    fn loads_reversed_ranges_without_failing() {
+        let dates: DateRange = serde_json::from_str(r#"["2020", "2019"]"#).unwrap();
+        assert_eq!(dates.start, Some(ResumeDate::Year(2020)));
+        assert!(dates.validate().is_err());
+    }
+
+    #[test]
+    fn counts_months_inclusively() {
+        assert_eq!(range("2021-03", "2021-03").months(), Some(1));
+        assert_eq!(range("2021-03", "2022-02").months(), Some(12));
+        assert_eq!(range("2020", "2021").months(), Some(24));
+        assert_eq!(
+            DateRange::new(Some(parse("2021-03")), None)
+                .unwrap()
+                .months(),
+            None
+        );
+        assert_eq!(DateRange::default().months(), None);
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(duration(0), "1 mo");
+        assert_eq!(duration(1), "1 mo");
+        assert_eq!(duration(4), "4 mos");
+        assert_eq!(duration(12), "1 yr");
+        assert_eq!(duration(24), "2 yrs");
+        assert_eq!(duration(13), "1 yr 1 mo");
+        assert_eq!(duration(28), "2 yrs 4 mos");
+    }
+
+    #[test]
+    fn formats_ranges_with_the_theme_style() {
+        let mut style = DateStyle::default();
+        assert_eq!(
+            range("2021-03", "2022-06").format(&style).unwrap(),
+            "Mar 2021 – Jun 2022"
+        );
+        assert_eq!(range("2021", "2021").format(&style).unwrap(), "2021");
+
+        style.durations = true;
+        assert_eq!(
+            range("2021-03", "2022-06").format(&style).unwrap(),
+            "Mar 2021 – Jun 2022 · 1 yr 4 mos"
+        );
+        assert_eq!(DateRange::default().format(&style), None);
+    }
+}
@@ -0,0 +1,190 @@
+use std::env;
+use std::io::{BufRead, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context};
+
+use super::api::DocumentShape;
+use super::errors::PortformError;
+use super::file_io::{ConfigFileHandler, ConfigFormat};
+use super::os;
+use super::theme::Theme;
+use super::validation;
+
+/*
+   Whole-config editing in the user's editor.
+   Changes go to a draft file first and only replace the stored config once they parse and validate.
+*/
+pub struct Editor;
+
+impl Editor {
+    pub fn edit_config(format: Option<ConfigFormat>) -> Result<(), anyhow::Error> {
+        let config_path = os::resolve_config_path()?.0;
+        let document_config = ConfigFileHandler::read_from(&config_path)?;
+
+        let format = format.unwrap_or_else(|| ConfigFormat::from_path(&config_path));
+        let original = format.serialize(&document_config)?;
+        let draft = Self::create_draft(format, &original)?;
+
+        loop {
+            Self::launch(&draft)?;
+
+            let edited = std::fs::read_to_string(&draft)
+                .with_context(|| format!("Unable to read {}", draft.display()))?;
+
+            if edited == original {
+                std::fs::remove_file(&draft)?;
+                println!("No changes made");
+                return Ok(());
+            }
+
+            match format
+                .parse(&edited)
+                .map_err(anyhow::Error::from)
+                .and_then(|mut document| {
+                    Self::validate(&mut document, &document_config).map(|_| document)
+                }) {
+                Ok(document) => {
+                    ConfigFileHandler::write_to(&config_path, &document)?;
+                    std::fs::remove_file(&draft)?;
+                    println!("Saved {}", config_path.display());
+                    return Ok(());
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+
+                    if !Self::confirm("Re-open the editor?")? {
+                        return Err(e.context(format!(
+                            "Config left unchanged. Your edits are kept in {}",
+                            draft.display()
+                        )));
+                    }
+                }
+            }
+        }
+    }
+
+    /*
+       Checks that parsing alone cannot express, normalizing contact details as `set contact` does.
+       Only values that differ from `original` are checked, so older values never block an edit.
+    */
+    fn validate(
+        document: &mut DocumentShape,
+        original: &DocumentShape,
+    ) -> Result<(), anyhow::Error> {
+        if let Some(contact) = &document.contact_details {
+            let before = original.contact_details.clone().unwrap_or_default();
+            let contact = validation::contact(contact, &before).map_err(|error| {
+                let (value, reason) = error.parts();
+                PortformError::Validation {
+                    field: format!("contact_details.{}", error.field()),
+                    value: Some(value.to_string()),
+                    message: reason.to_string(),
+                }
+            })?;
+            document.contact_details = Some(contact);
+        }
+
+        if let Some(theme) = document
+            .theme
+            .as_ref()
+            .filter(|t| original.theme.as_ref() != Some(t))
+        {
+            Theme::builtin(theme)?;
+        }
+
+        let before = original.date_ranges();
+
+        for (section, index, dates, e) in document.invalid_date_ranges() {
+            if !before.contains(&(section, index, dates)) {
+                Err(PortformError::validation(
+                    &format!("{}[{}].dates", section, index),
+                    e,
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /*
+       The draft holds the whole resume, so it lives in the private config directory
+       and is created fresh with owner-only permissions, never through an existing path.
+       The extension lets the editor pick the right syntax highlighting.
+    */
+    fn create_draft(format: ConfigFormat, contents: &str) -> Result<PathBuf, anyhow::Error> {
+        let dir = os::config_dir()?.0;
+        os::create_private_dir(&dir)?;
+
+        // A draft kept from an earlier failed edit may already use this process id
+        for attempt in 0..100 {
+            let draft = dir.join(format!(
+                "edit-{}-{}.{}",
+                std::process::id(),
+                attempt,
+                format.extension()
+            ));
+
+            match os::create_private_file(&draft) {
+                Ok(mut file) => {
+                    file.write_all(contents.as_bytes())
+                        .with_context(|| format!("Unable to write {}", draft.display()))?;
+                    return Ok(draft);
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Unable to create {}", draft.display()))
+                }
+            }
+        }
+
+        bail!("Unable to create a draft in {}", dir.display())
+    }
+
+    // $VISUAL wins over $EDITOR, and either may carry arguments such as `code --wait`
+    fn launch(file: &Path) -> Result<(), anyhow::Error> {
+        let editor = ["VISUAL", "EDITOR"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+
+        let mut words = editor.split_whitespace();
+        let program = words.next().unwrap_or_default();
+
+        let status = Command::new(program)
+            .args(words)
+            .arg(file)
+            .status()
+            .with_context(|| format!("Unable to launch editor {:?}", editor))?;
+
+        if !status.success() {
+            bail!(
+                "Editor {:?} exited with {}. Your edits are kept in {}",
+                editor,
+                status,
+                file.display()
+            );
+        }
+
+        Ok(())
+    }
+
+    // Defaults to yes, so that pressing enter goes back to the edits
+    fn confirm(question: &str) -> Result<bool, anyhow::Error> {
+        print!("{} [Y/n] ", question);
+        std::io::stdout().flush()?;
+
+        let mut answer = String::new();
+
+        if std::io::stdin().lock().read_line(&mut answer)? == 0 {
+            return Ok(false);
+        }
+
+        Ok(!matches!(
+            answer.trim().to_ascii_lowercase().as_str(),
+            "n" | "no"
+        ))
+    }
+}
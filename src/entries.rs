@@ -0,0 +1,360 @@
+use super::api::{Dated, DocumentShape, Labeled};
+use super::errors::PortformError;
+use super::theme::SectionKind;
+
+// Index-checked manipulation of the list sections of a `DocumentShape`
+pub struct Entries;
+
+impl Entries {
+    pub fn get_mut<'a, T>(
+        section: &'a mut Option<Vec<T>>,
+        index: usize,
+        name: &str,
+    ) -> Result<&'a mut T, anyhow::Error> {
+        let entries = Self::non_empty(section, name)?;
+        let len = entries.len();
+
+        entries
+            .get_mut(index)
+            .ok_or_else(|| Self::out_of_range(index, len, name))
+    }
+
+    /// Remove by index, or by case-insensitive label when `target` is not a number
+    pub fn remove<T: Labeled>(
+        section: &mut Option<Vec<T>>,
+        target: &str,
+        name: &str,
+    ) -> Result<T, anyhow::Error> {
+        let entries = Self::non_empty(section, name)?;
+
+        let index = match target.parse::<usize>() {
+            Ok(index) if index < entries.len() => index,
+            Ok(index) => return Err(Self::out_of_range(index, entries.len(), name)),
+            Err(_) => entries
+                .iter()
+                .position(|entry| entry.label().eq_ignore_ascii_case(target))
+                .ok_or_else(|| {
+                    PortformError::validation(
+                        "entry",
+                        format!("no {} entry named {:?}", name, target),
+                    )
+                })?,
+        };
+
+        let removed = entries.remove(index);
+
+        if entries.is_empty() {
+            *section = None;
+        }
+
+        Ok(removed)
+    }
+
+    pub fn relocate<T>(
+        section: &mut Option<Vec<T>>,
+        from: usize,
+        to: usize,
+        name: &str,
+    ) -> Result<(), anyhow::Error> {
+        let entries = Self::non_empty(section, name)?;
+        let len = entries.len();
+
+        for index in [from, to] {
+            if index >= len {
+                return Err(Self::out_of_range(index, len, name));
+            }
+        }
+
+        let entry = entries.remove(from);
+        entries.insert(to, entry);
+
+        Ok(())
+    }
+
+    /// Newest first, with pinned entries on top and undated ones last, otherwise keeping order
+    pub fn sort_by_date<T: Dated>(section: &mut Option<Vec<T>>) {
+        if let Some(entries) = section {
+            entries.sort_by_key(|entry| std::cmp::Reverse((entry.pinned(), entry.recency())));
+        }
+    }
+
+    // Sections without dates are left as they are
+    pub fn sort_section(document: &mut DocumentShape, kind: SectionKind) {
+        match kind {
+            SectionKind::Employment => Self::sort_by_date(&mut document.employment_history),
+            SectionKind::Education => Self::sort_by_date(&mut document.education),
+            SectionKind::Certifications => Self::sort_by_date(&mut document.certifications),
+            _ => {}
+        }
+    }
+
+    // Applied when writing, so entries added out of order still come out newest first
+    pub fn sort_dated_sections(document: &mut DocumentShape) {
+        for kind in [
+            SectionKind::Employment,
+            SectionKind::Education,
+            SectionKind::Certifications,
+        ] {
+            if !document.manual_order.contains(&kind) {
+                Self::sort_section(document, kind);
+            }
+        }
+    }
+
+    fn non_empty<'a, T>(
+        section: &'a mut Option<Vec<T>>,
+        name: &str,
+    ) -> Result<&'a mut Vec<T>, anyhow::Error> {
+        match section {
+            Some(entries) if !entries.is_empty() => Ok(entries),
+            _ => Err(PortformError::validation(
+                "index",
+                format!("there are no {} entries yet", name),
+            ))?,
+        }
+    }
+
+    fn out_of_range(index: usize, len: usize, name: &str) -> anyhow::Error {
+        PortformError::validation(
+            "index",
+            format!(
+                "{} is out of range: {} has {} entries (valid indices are 0..={})",
+                index,
+                name,
+                len,
+                len - 1
+            ),
+        )
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Certification, HistoryEntry, Skill};
+    use crate::dates::{DateRange, ResumeDate};
+
+    fn job(position: &str, start: &str, end: &str) -> HistoryEntry {
+        let date = |text: &str| {
+            Some(text.to_string())
+                .filter(|t| !t.is_empty())
+                .map(ResumeDate::lenient)
+        };
+
+        HistoryEntry {
+            position: position.to_string(),
+            location: String::new(),
+            dates_employed: DateRange {
+                start: date(start),
+                end: date(end),
+            },
+            description: String::new(),
+            pinned: false,
+        }
+    }
+
+    fn sorted(jobs: Vec<HistoryEntry>) -> Vec<String> {
+        let mut section = Some(jobs);
+        Entries::sort_by_date(&mut section);
+        section
+            .unwrap()
+            .into_iter()
+            .map(|job| job.position)
+            .collect()
+    }
+
+    #[test]
+    fn sorts_newest_first() {
+        assert_eq!(
+            sorted(vec![
+                job("old", "2010", "2012"),
+                job("new", "2019-01", "2021-06"),
+                job("mid", "2013", "2018"),
+            ]),
+            ["new", "mid", "old"]
+        );
+    }
+
+    #[test]
+    fn sorts_certifications_by_issue_date() {
+        let cert = |name: &str, issued: &str| Certification {
+            date_issued: Some(ResumeDate::lenient(issued.to_string())),
+            name: name.to_string(),
+            pinned: false,
+        };
+        let mut section = Some(vec![
+            cert("older", "2019-05"),
+            cert("newer", "2019-11"),
+            Certification {
+                date_issued: None,
+                ..cert("undated", "")
+            },
+        ]);
+        Entries::sort_by_date(&mut section);
+
+        let names: Vec<_> = section.iter().flatten().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["newer", "older", "undated"]);
+    }
+
+    #[test]
+    fn keeps_pinned_entries_on_top() {
+        let mut pinned = job("pinned", "2001", "2002");
+        pinned.pinned = true;
+
+        assert_eq!(
+            sorted(vec![job("new", "2020", "present"), pinned]),
+            ["pinned", "new"]
+        );
+    }
+
+    #[test]
+    fn puts_ongoing_entries_before_ended_ones() {
+        assert_eq!(
+            sorted(vec![
+                job("ended", "2020", "2099"),
+                job("current", "2015", "present"),
+            ]),
+            ["current", "ended"]
+        );
+    }
+
+    #[test]
+    fn puts_undated_and_free_text_dates_last() {
+        assert_eq!(
+            sorted(vec![
+                job("undated", "", ""),
+                job("text", "Spring '20", "Fall '20"),
+                job("dated", "2005", "2006"),
+            ]),
+            ["dated", "undated", "text"]
+        );
+    }
+
+    #[test]
+    fn keeps_the_stored_order_of_ties() {
+        assert_eq!(
+            sorted(vec![
+                job("first", "2020", "2021"),
+                job("second", "2020", "2021"),
+                job("third", "2020", "2021"),
+            ]),
+            ["first", "second", "third"]
+        );
+    }
+
+    #[test]
+    fn leaves_sections_in_manual_order_untouched() {
+        let mut document = DocumentShape {
+            employment_history: Some(vec![job("old", "2010", "2011"), job("new", "2020", "2021")]),
+            education: None,
+            manual_order: vec![SectionKind::Employment],
+            ..DocumentShape::default()
+        };
+        Entries::sort_dated_sections(&mut document);
+
+        let positions: Vec<_> = document
+            .employment_history
+            .iter()
+            .flatten()
+            .map(|job| job.position.as_str())
+            .collect();
+        assert_eq!(positions, ["old", "new"]);
+
+        document.manual_order.clear();
+        Entries::sort_dated_sections(&mut document);
+        assert_eq!(document.employment_history.unwrap()[0].position, "new");
+    }
+
+    fn skills(names: &[&str]) -> Option<Vec<Skill>> {
+        Some(
+            names
+                .iter()
+                .map(|name| Skill {
+                    name: name.to_string(),
+                })
+                .collect(),
+        )
+    }
+
+    fn exit_code(error: anyhow::Error) -> u8 {
+        PortformError::exit_code_of(&error)
+    }
+
+    #[test]
+    fn rejects_out_of_range_indices() {
+        let mut section = skills(&["Rust", "Go"]);
+
+        let error = Entries::get_mut(&mut section, 2, "skills").unwrap_err();
+        assert!(
+            error.to_string().contains("valid indices are 0..=1"),
+            "{}",
+            error
+        );
+        assert_eq!(exit_code(error), 7);
+
+        assert!(Entries::remove(&mut section, "5", "skills").is_err());
+        assert!(Entries::relocate(&mut section, 0, 2, "skills").is_err());
+        assert!(Entries::relocate(&mut section, 2, 0, "skills").is_err());
+        assert_eq!(section.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn rejects_empty_and_missing_sections() {
+        for mut section in [None, Some(Vec::<Skill>::new())] {
+            let error = Entries::get_mut(&mut section, 0, "skills").unwrap_err();
+            assert!(
+                error.to_string().contains("no skills entries yet"),
+                "{}",
+                error
+            );
+            assert!(Entries::remove(&mut section, "Rust", "skills").is_err());
+            assert!(Entries::relocate(&mut section, 0, 0, "skills").is_err());
+        }
+    }
+
+    #[test]
+    fn removes_by_label_ignoring_case() {
+        let mut section = skills(&["Rust", "Go"]);
+
+        assert_eq!(
+            Entries::remove(&mut section, "rUST", "skills")
+                .unwrap()
+                .name,
+            "Rust"
+        );
+        assert!(Entries::remove(&mut section, "Python", "skills").is_err());
+        assert_eq!(section.unwrap()[0].name, "Go");
+    }
+
+    #[test]
+    fn reads_numeric_targets_as_indices() {
+        // A label that is a number can only be reached by its index
+        let mut section = skills(&["2024", "Rust"]);
+
+        assert!(Entries::remove(&mut section, "2024", "skills").is_err());
+        assert_eq!(
+            Entries::remove(&mut section, "1", "skills").unwrap().name,
+            "Rust"
+        );
+    }
+
+    #[test]
+    fn clears_the_section_when_its_last_entry_is_removed() {
+        let mut section = skills(&["Rust"]);
+
+        Entries::remove(&mut section, "0", "skills").unwrap();
+        assert!(section.is_none());
+    }
+
+    #[test]
+    fn moves_entries_and_edits_in_place() {
+        let mut section = skills(&["a", "b", "c"]);
+
+        Entries::relocate(&mut section, 0, 2, "skills").unwrap();
+        Entries::get_mut(&mut section, 0, "skills").unwrap().name = "B".to_string();
+
+        let names: Vec<_> = section.iter().flatten().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["B", "c", "a"]);
+    }
+}
@@ -1,3 +1,220 @@
-use anyhow;
+use std::fmt;
+use std::path::PathBuf;
 
-pub type Error = anyhow::Error;
+// A contact detail that was rejected, naming the field and what is wrong with it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    Email { value: String, reason: String },
+    Website { value: String, reason: String },
+    Phone { value: String, reason: String },
+    Address { value: String, reason: String },
+}
+
+impl ValidationError {
+    // Matches the `set contact` flag for the field
+    pub fn field(&self) -> &'static str {
+        match self {
+            Self::Email { .. } => "email",
+            Self::Website { .. } => "website",
+            Self::Phone { .. } => "phone",
+            Self::Address { .. } => "address",
+        }
+    }
+
+    // The rejected value and why it was rejected
+    pub fn parts(&self) -> (&str, &str) {
+        match self {
+            Self::Email { value, reason }
+            | Self::Website { value, reason }
+            | Self::Phone { value, reason }
+            | Self::Address { value, reason } => (value, reason),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (value, reason) = self.parts();
+        write!(f, "Invalid --{} {:?}: {}", self.field(), value, reason)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/*
+   Failures that scripts wrapping portform may want to tell apart.
+   Each maps to its own process exit code; anything else exits with 1,
+   and clap exits with 2 for invalid arguments.
+*/
+#[derive(Debug)]
+pub enum PortformError {
+    ConfigMissing {
+        path: PathBuf,
+    },
+    ConfigCorrupt {
+        path: PathBuf,
+        syntax: &'static str,
+        line: Option<usize>,
+        col: Option<usize>,
+        message: String,
+    },
+    FontLoad {
+        style: &'static str,
+        path: PathBuf,
+        reason: String,
+    },
+    OutputWrite {
+        path: PathBuf,
+        reason: String,
+    },
+    Validation {
+        field: String,
+        value: Option<String>,
+        message: String,
+    },
+    UnsupportedPlatform {
+        os: String,
+    },
+    // Only raised by `lint --strict`, so CI can fail on a warning
+    LintFailed {
+        warnings: usize,
+    },
+}
+
+impl PortformError {
+    pub const EXIT_CODES: [(u8, &'static str); 7] = [
+        (3, "no config file was found"),
+        (4, "the config file could not be parsed"),
+        (5, "a font could not be loaded"),
+        (6, "an output file could not be written"),
+        (
+            7,
+            "a value failed validation, or init/import refused to replace a config without --force",
+        ),
+        (8, "the operating system is not supported"),
+        (9, "`lint --strict` found warnings"),
+    ];
+
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::ConfigMissing { .. } => 3,
+            Self::ConfigCorrupt { .. } => 4,
+            Self::FontLoad { .. } => 5,
+            Self::OutputWrite { .. } => 6,
+            Self::Validation { .. } => 7,
+            Self::UnsupportedPlatform { .. } => 8,
+            Self::LintFailed { .. } => 9,
+        }
+    }
+
+    // The first typed error in the chain decides the exit code
+    pub fn exit_code_of(error: &anyhow::Error) -> u8 {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<Self>())
+            .map_or(1, Self::exit_code)
+    }
+
+    pub fn output_write(path: impl Into<PathBuf>, error: impl fmt::Display) -> Self {
+        Self::OutputWrite {
+            path: path.into(),
+            reason: error.to_string(),
+        }
+    }
+
+    pub fn validation(field: &str, error: impl fmt::Display) -> Self {
+        Self::Validation {
+            field: field.to_string(),
+            value: None,
+            message: error.to_string(),
+        }
+    }
+
+    // Listed under `portform --help`
+    pub fn exit_code_help() -> String {
+        let mut help = String::from("Exit codes:\n  1  any other error\n  2  invalid arguments");
+        for (code, meaning) in Self::EXIT_CODES {
+            help.push_str(&format!("\n  {}  {}", code, meaning));
+        }
+        help
+    }
+}
+
+impl fmt::Display for PortformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConfigMissing { path } => write!(
+                f,
+                "No config found at {}. Run `portform init` to create one.",
+                path.display()
+            ),
+            Self::ConfigCorrupt {
+                path,
+                syntax,
+                line,
+                col,
+                message,
+            } => match (line, col) {
+                (Some(line), Some(col)) => write!(
+                    f,
+                    "{}: invalid {} at line {}, column {}: {}",
+                    path.display(),
+                    syntax,
+                    line,
+                    col,
+                    message
+                ),
+                _ => write!(f, "{}: invalid {}: {}", path.display(), syntax, message),
+            },
+            Self::FontLoad {
+                style,
+                path,
+                reason,
+            } => write!(
+                f,
+                "Unable to load the {} font at {}: {}",
+                style,
+                path.display(),
+                reason
+            ),
+            Self::OutputWrite { path, reason } => {
+                write!(f, "Unable to write {}: {}", path.display(), reason)
+            }
+            Self::Validation {
+                field,
+                value: Some(value),
+                message,
+            } => write!(f, "Invalid {} {:?}: {}", field, value, message),
+            Self::Validation {
+                field,
+                value: None,
+                message,
+            } => write!(f, "Invalid {}: {}", field, message),
+            Self::UnsupportedPlatform { os } => write!(
+                f,
+                "{} is not currently supported. Pass --config to use a config file directly.",
+                os
+            ),
+            Self::LintFailed { warnings } => write!(
+                f,
+                "Lint found {} warning{}",
+                warnings,
+                if *warnings == 1 { "" } else { "s" }
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PortformError {}
+
+impl From<ValidationError> for PortformError {
+    fn from(error: ValidationError) -> Self {
+        let (value, reason) = error.parts();
+
+        Self::Validation {
+            field: format!("--{}", error.field()),
+            value: Some(value.to_string()),
+            message: reason.to_string(),
+        }
+    }
+}
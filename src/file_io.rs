@@ -1,10 +1,344 @@
 use super::api::DocumentShape;
+use super::errors::PortformError;
 use super::os;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::Path;
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, Value};
 pub struct ConfigFileHandler;
 
+// On-disk syntax of a config file, chosen by its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+// A config that could not be parsed, with the 1-based position of the problem when known
+#[derive(Debug)]
+pub struct ParseError {
+    pub syntax: &'static str,
+    pub message: String,
+    pub location: Option<(usize, usize)>,
+}
+
+impl ParseError {
+    fn new(syntax: &'static str, message: &str, location: Option<(usize, usize)>) -> Self {
+        // serde_json and serde_yaml append the position, which is reported separately here
+        let message = match message.rsplit_once(" at line ") {
+            Some((message, _)) if location.is_some() => message,
+            _ => message,
+        };
+
+        Self {
+            syntax,
+            message: message.trim().to_string(),
+            location,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.location {
+            Some((line, column)) => write!(
+                f,
+                "invalid {} at line {}, column {}: {}",
+                self.syntax, line, column, self.message
+            ),
+            None => write!(f, "invalid {}: {}", self.syntax, self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Convert a byte offset into a 1-based line and column
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+
+    (line, column)
+}
+
+impl ConfigFormat {
+    pub const EXTENSIONS: [&'static str; 4] = ["json", "yaml", "yml", "toml"];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Toml => "toml",
+        }
+    }
+
+    // Anything unrecognized is read as JSON, which is what older versions always wrote
+    pub fn from_path(path: &Path) -> Self {
+        Self::from_extension(path.extension().and_then(|ext| ext.to_str()).unwrap_or(""))
+    }
+
+    pub fn from_extension(extension: &str) -> Self {
+        match extension.to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Self::Yaml,
+            "toml" => Self::Toml,
+            _ => Self::Json,
+        }
+    }
+
+    pub fn parse(self, text: &str) -> Result<DocumentShape, ParseError> {
+        match self {
+            Self::Json => serde_json::from_str(text).map_err(|e| {
+                let location = Some((e.line(), e.column())).filter(|&(line, _)| line > 0);
+                ParseError::new("JSON", &e.to_string(), location)
+            }),
+            Self::Yaml => serde_yaml::from_str(text).map_err(|e| {
+                let location = e.location().map(|l| (l.line(), l.column()));
+                ParseError::new("YAML", &e.to_string(), location)
+            }),
+            Self::Toml => toml::from_str(text).map_err(|e| {
+                let location = e.span().map(|span| line_column(text, span.start));
+                ParseError::new("TOML", e.message(), location)
+            }),
+        }
+    }
+
+    // Pretty-printed so the file stays pleasant to edit by hand and to diff
+    pub fn serialize(self, data: &DocumentShape) -> Result<String, anyhow::Error> {
+        Ok(match self {
+            Self::Json => serde_json::to_string_pretty(data)? + "\n",
+            Self::Yaml => serde_yaml::to_string(data)?,
+            Self::Toml => toml::to_string_pretty(data)?,
+        })
+    }
+
+    /*
+       Serialize over the text of an existing config, returning the new text
+       and the top-level keys whose comments could not be kept.
+       TOML is edited in place, so comments, key order and formatting of unchanged values survive.
+       YAML is patched one top-level key at a time: unchanged keys keep their text and comments,
+       while a changed key is written fresh, losing any comments inside it.
+       JSON has no comments to keep and is written fresh.
+    */
+    pub fn serialize_over(
+        self,
+        existing: &str,
+        data: &DocumentShape,
+    ) -> Result<(String, Vec<String>), anyhow::Error> {
+        let updated = self.serialize(data)?;
+
+        match self {
+            Self::Toml => {
+                if let Ok(mut document) = existing.parse::<DocumentMut>() {
+                    merge_table(
+                        document.as_table_mut(),
+                        updated.parse::<DocumentMut>()?.as_table(),
+                    );
+                    return Ok((document.to_string(), Vec::new()));
+                }
+            }
+            Self::Yaml => {
+                if let Some(merged) = merge_yaml(existing, &updated) {
+                    return Ok(merged);
+                }
+
+                // Not split into top-level keys, e.g. flow style or several documents
+                if existing
+                    .lines()
+                    .any(|line| line.trim_start().starts_with('#'))
+                {
+                    let keys = yaml_blocks(&updated)
+                        .map(|(_, blocks, _)| blocks.iter().map(YamlBlock::name).collect())
+                        .unwrap_or_default();
+                    return Ok((updated, keys));
+                }
+            }
+            Self::Json => {}
+        }
+
+        Ok((updated, Vec::new()))
+    }
+}
+
+// One top-level key of a YAML document, with the comments and blank lines written above it
+struct YamlBlock {
+    leading: String,
+    body: String,
+    key: serde_yaml::Value,
+    value: serde_yaml::Value,
+}
+
+impl YamlBlock {
+    fn name(&self) -> String {
+        self.key.as_str().unwrap_or("?").to_string()
+    }
+
+    fn has_comments(&self) -> bool {
+        self.body
+            .lines()
+            .any(|line| line.trim_start().starts_with('#') || line.contains(" #"))
+    }
+}
+
+/*
+   Split a block-style YAML document at its top-level keys, returning the text before the first key,
+   the keys and the text after the last one. None when a block does not parse as a single key on its own.
+*/
+fn yaml_blocks(text: &str) -> Option<(String, Vec<YamlBlock>, String)> {
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let is_key = |line: &&str| {
+        !line.trim().is_empty() && !line.starts_with([' ', '\t', '#', '-', '.', '%', '\r', '\n'])
+    };
+    let starts: Vec<usize> = (0..lines.len()).filter(|&i| is_key(&lines[i])).collect();
+    let first = *starts.first()?;
+
+    let preamble = lines[..first].concat();
+    let mut blocks = Vec::new();
+    let mut leading = String::new();
+
+    for (n, &start) in starts.iter().enumerate() {
+        let end = starts.get(n + 1).copied().unwrap_or(lines.len());
+
+        // Comments and blank lines at column 0 before the next key belong to that key
+        let mut body_end = end;
+        while body_end > start + 1 {
+            let line = lines[body_end - 1];
+            if line.trim().is_empty() || line.starts_with('#') {
+                body_end -= 1;
+            } else {
+                break;
+            }
+        }
+
+        let body = lines[start..body_end].concat();
+        let mapping: serde_yaml::Mapping = serde_yaml::from_str(&body).ok()?;
+        if mapping.len() != 1 {
+            return None;
+        }
+        let (key, value) = mapping.into_iter().next()?;
+
+        blocks.push(YamlBlock {
+            leading: std::mem::take(&mut leading),
+            body,
+            key,
+            value,
+        });
+
+        leading = lines[body_end..end].concat();
+    }
+
+    Some((preamble, blocks, leading))
+}
+
+// Patch `existing` with the top-level keys of `updated` that differ, or None when it cannot be split
+fn merge_yaml(existing: &str, updated: &str) -> Option<(String, Vec<String>)> {
+    let (preamble, old, tail) = yaml_blocks(existing)?;
+    let (_, new, _) = yaml_blocks(updated)?;
+
+    let mut text = preamble;
+    let mut dropped = Vec::new();
+    let push = |text: &mut String, part: &str| {
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(part);
+    };
+
+    // Removed keys go with the comments above them
+    for block in &old {
+        let Some(replacement) = new.iter().find(|n| n.key == block.key) else {
+            continue;
+        };
+
+        push(&mut text, &block.leading);
+
+        if replacement.value == block.value {
+            push(&mut text, &block.body);
+        } else {
+            if block.has_comments() {
+                dropped.push(block.name());
+            }
+            push(&mut text, &replacement.body);
+        }
+    }
+
+    for block in new.iter().filter(|n| !old.iter().any(|o| o.key == n.key)) {
+        push(&mut text, &block.body);
+    }
+
+    push(&mut text, &tail);
+
+    Some((text, dropped))
+}
+
+// Bring `target` in line with `source`, touching only the entries that differ
+fn merge_table(target: &mut Table, source: &Table) {
+    target.retain(|key, _| source.contains_key(key));
+
+    for (key, item) in source.iter() {
+        match (target.get_mut(key), item) {
+            (Some(Item::Table(target)), Item::Table(source)) => merge_table(target, source),
+            (Some(Item::ArrayOfTables(target)), Item::ArrayOfTables(source)) => {
+                while target.len() > source.len() {
+                    target.remove(target.len() - 1);
+                }
+                for (index, table) in source.iter().enumerate() {
+                    match target.get_mut(index) {
+                        Some(existing) => merge_table(existing, table),
+                        None => target.push(detached_table(table)),
+                    }
+                }
+            }
+            (Some(Item::Value(target)), Item::Value(source)) => {
+                if !same_value(target, source) {
+                    let decor = target.decor().clone();
+                    *target = source.clone();
+                    *target.decor_mut() = decor;
+                }
+            }
+            _ => {
+                target.insert(key, detached(item));
+            }
+        }
+    }
+}
+
+/*
+   Copies of new tables drop their position in the freshly serialized document,
+   so they are written after their neighbours in the existing one instead of among unrelated tables.
+*/
+fn detached(item: &Item) -> Item {
+    match item {
+        Item::Table(table) => Item::Table(detached_table(table)),
+        Item::ArrayOfTables(array) => {
+            Item::ArrayOfTables(array.iter().map(detached_table).collect::<ArrayOfTables>())
+        }
+        item => item.clone(),
+    }
+}
+
+fn detached_table(table: &Table) -> Table {
+    let mut copy = Table::new();
+    copy.set_implicit(table.is_implicit());
+    for (key, item) in table.iter() {
+        copy.insert(key, detached(item));
+    }
+    copy
+}
+
+// Compared by content, so a value written as 'literal' or reformatted by hand is left alone
+fn same_value(a: &Value, b: &Value) -> bool {
+    let content = |value: &Value| {
+        let mut value = value.clone();
+        value.decor_mut().clear();
+        format!("v = {}", value).parse::<toml::Table>().ok()
+    };
+
+    content(a).is_some_and(|a| Some(a) == content(b))
+}
+
 pub trait FileHandler<'a, T: Serialize + Deserialize<'a>> {
     fn read() -> Result<T, anyhow::Error>;
     fn write(data: T) -> Result<(), anyhow::Error>;
@@ -12,28 +346,203 @@ pub trait FileHandler<'a, T: Serialize + Deserialize<'a>> {
 
 impl FileHandler<'_, DocumentShape> for ConfigFileHandler {
     fn read() -> Result<DocumentShape, anyhow::Error> {
-        let temp_path = os::get_os_config_path()?.0;
-        let json = std::fs::read_to_string(temp_path)?;
-        let document_config: DocumentShape = serde_json::from_str(&json)?;
+        Self::read_from(&os::resolve_config_path()?.0)
+    }
+
+    fn write(data: DocumentShape) -> Result<(), anyhow::Error> {
+        Self::write_to(&os::resolve_config_path()?.0, &data)
+    }
+}
+
+impl ConfigFileHandler {
+    pub fn read_from(config_path: &Path) -> Result<DocumentShape, anyhow::Error> {
+        let text = match std::fs::read_to_string(config_path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(PortformError::ConfigMissing {
+                path: config_path.to_path_buf(),
+            })?,
+            result => result?,
+        };
+        let document_config = ConfigFormat::from_path(config_path)
+            .parse(&text)
+            .map_err(|e| PortformError::ConfigCorrupt {
+                path: config_path.to_path_buf(),
+                syntax: e.syntax,
+                line: e.location.map(|(line, _)| line),
+                col: e.location.map(|(_, col)| col),
+                message: e.message,
+            })?;
 
         Ok(document_config)
     }
 
-    fn write(data: DocumentShape) -> Result<(), anyhow::Error> {
-        let json = serde_json::to_string(&data).unwrap();
-        let temp_path = os::get_os_config_path()?.0;
+    pub fn write_to(config_path: &Path, data: &DocumentShape) -> Result<(), anyhow::Error> {
+        let format = ConfigFormat::from_path(config_path);
+        let (text, dropped) = match std::fs::read_to_string(config_path) {
+            Ok(existing) => format.serialize_over(&existing, data)?,
+            Err(_) => (format.serialize(data)?, Vec::new()),
+        };
+
+        if !dropped.is_empty() {
+            eprintln!(
+                "Comments under {} in {} could not be kept, since those keys were rewritten",
+                dropped.join(", "),
+                config_path.display()
+            );
+        }
+
+        if let Some(parent) = config_path.parent() {
+            os::create_private_dir(parent)?;
+        }
 
-        let file = File::create(temp_path)?;
+        let file =
+            File::create(config_path).map_err(|e| PortformError::output_write(config_path, e))?;
         let mut buf_writer = BufWriter::new(file);
-        buf_writer.write_all(json.as_bytes())?;
-        buf_writer.flush()?;
+        buf_writer
+            .write_all(text.as_bytes())
+            .and_then(|_| buf_writer.flush())
+            .map_err(|e| PortformError::output_write(config_path, e))?;
+
+        os::restrict_permissions(config_path)?;
         Ok(())
     }
+
+    pub fn exists() -> Result<bool, anyhow::Error> {
+        Ok(os::resolve_config_path()?.0.is_file())
+    }
+
+    // Commands that modify the config start from the defaults on a fresh install
+    pub fn read_or_default() -> Result<DocumentShape, anyhow::Error> {
+        if Self::exists()? {
+            Self::read()
+        } else {
+            Ok(DocumentShape::default())
+        }
+    }
+
+    pub fn init_write_file(filename: impl AsRef<Path>) -> Result<BufWriter<File>, PortformError> {
+        let filename = filename.as_ref();
+        let write_file = std::fs::File::create(filename)
+            .map_err(|e| PortformError::output_write(filename, e))?;
+
+        Ok(BufWriter::new(write_file))
+    }
 }
 
-impl ConfigFileHandler {
-    pub fn init_write_file(filename: String) -> BufWriter<File> {
-        let write_file = std::fs::File::create(filename).unwrap();
-        BufWriter::new(write_file)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Skill;
+
+    const EXISTING: &str = "# My resume\nfilename = 'out.pdf'  # kept as written\ntitle = \"Resume\"\n\n[header]\n# who I am\nname = \"Jane Doe\"\nprofession = \"Engineer\"\n\n[[skillset]]\nname = \"Rust\"   # favourite\n";
+
+    fn document() -> DocumentShape {
+        ConfigFormat::Toml.parse(EXISTING).unwrap()
+    }
+
+    #[test]
+    fn leaves_unset_fields_out() {
+        let data = DocumentShape {
+            title: None,
+            ..Default::default()
+        };
+
+        let json = ConfigFormat::Json.serialize(&data).unwrap();
+        let yaml = ConfigFormat::Yaml.serialize(&data).unwrap();
+        assert_eq!(json, "{\n  \"filename\": \"My_Current_Resume.pdf\"\n}\n");
+        assert_eq!(yaml, "filename: My_Current_Resume.pdf\n");
+    }
+
+    #[test]
+    fn keeps_toml_comments_and_formatting_of_unchanged_values() {
+        let mut data = document();
+        data.title = Some("Engineer".to_string());
+
+        let (text, _) = ConfigFormat::Toml.serialize_over(EXISTING, &data).unwrap();
+        assert_eq!(text, EXISTING.replace("\"Resume\"", "\"Engineer\""));
+    }
+
+    #[test]
+    fn places_new_toml_tables_beside_their_neighbours() {
+        let mut data = document();
+        data.header = None;
+        if let Some(skills) = data.skillset.as_mut() {
+            skills.push(Skill {
+                name: "Go".to_string(),
+            });
+        }
+
+        let (text, _) = ConfigFormat::Toml.serialize_over(EXISTING, &data).unwrap();
+        assert!(!text.contains("[header]"));
+        assert!(text.starts_with("# My resume\n"));
+        assert!(text.ends_with("name = \"Rust\"   # favourite\n\n[[skillset]]\nname = \"Go\"\n"));
+        assert_eq!(
+            ConfigFormat::Toml
+                .parse(&text)
+                .unwrap()
+                .skillset
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn writes_json_and_yaml_fresh() {
+        let data = document();
+
+        for format in [ConfigFormat::Json, ConfigFormat::Yaml] {
+            let (text, dropped) = format.serialize_over("{}", &data).unwrap();
+            assert_eq!(text, format.serialize(&data).unwrap(), "{:?}", format);
+            assert!(dropped.is_empty());
+        }
+
+        let yaml = ConfigFormat::Yaml.serialize(&data).unwrap();
+        assert!(yaml.contains("name: Jane Doe\n"));
+        assert_eq!(ConfigFormat::Yaml.parse(&yaml).unwrap().title, data.title);
+    }
+
+    const EXISTING_YAML: &str = "# My resume\ntitle: Resume   # shown in the PDF\n\n# who I am\nheader:\n  name: Jane Doe\n  profession: Engineer\nskillset:\n# favourites first\n- name: Rust\n";
+
+    #[test]
+    fn keeps_yaml_comments_and_order_of_unchanged_keys() {
+        let mut data = ConfigFormat::Yaml.parse(EXISTING_YAML).unwrap();
+        if let Some(skills) = data.skillset.as_mut() {
+            skills.push(Skill {
+                name: "Go".to_string(),
+            });
+        }
+
+        let (text, dropped) = ConfigFormat::Yaml
+            .serialize_over(EXISTING_YAML, &data)
+            .unwrap();
+        assert!(text.starts_with(
+            "# My resume\ntitle: Resume   # shown in the PDF\n\n# who I am\nheader:\n  name: Jane Doe\n"
+        ));
+        assert!(text.ends_with("skillset:\n- name: Rust\n- name: Go\n"));
+        assert_eq!(dropped, ["skillset"]);
+        assert_eq!(
+            ConfigFormat::Yaml
+                .parse(&text)
+                .unwrap()
+                .skillset
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn adds_and_removes_yaml_keys() {
+        let mut data = ConfigFormat::Yaml.parse(EXISTING_YAML).unwrap();
+        data.header = None;
+        data.theme = Some("modern".to_string());
+
+        let (text, dropped) = ConfigFormat::Yaml
+            .serialize_over(EXISTING_YAML, &data)
+            .unwrap();
+        assert!(!text.contains("header") && !text.contains("who I am"));
+        assert!(text.contains("# favourites first\n"));
+        assert!(text.ends_with("theme: modern\n"));
+        assert!(dropped.is_empty());
     }
 }
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::anyhow;
+use printpdf::{BuiltinFont, IndirectFontRef, PdfDocumentReference};
+use serde::{Deserialize, Serialize};
+
+use super::api::FontFamily;
+use super::errors::PortformError;
+use super::layout::FontMetrics;
+
+/*
+   Lucida Console ships inside the binary so `write` works from any directory.
+   It has only a regular face: bold text is simulated by stroking the glyph outlines
+   unless a bold face is set with `portform set fonts`.
+*/
+const EMBEDDED_REGULAR: &[u8] = include_bytes!("../assets/fonts/lucon.ttf");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FontStyle {
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic,
+}
+
+impl FontStyle {
+    pub fn new(bold: bool, italic: bool) -> Self {
+        match (bold, italic) {
+            (false, false) => FontStyle::Regular,
+            (true, false) => FontStyle::Bold,
+            (false, true) => FontStyle::Italic,
+            (true, true) => FontStyle::BoldItalic,
+        }
+    }
+
+    fn is_bold(self) -> bool {
+        matches!(self, FontStyle::Bold | FontStyle::BoldItalic)
+    }
+
+    fn is_italic(self) -> bool {
+        matches!(self, FontStyle::Italic | FontStyle::BoldItalic)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            FontStyle::Regular => "regular",
+            FontStyle::Bold => "bold",
+            FontStyle::Italic => "italic",
+            FontStyle::BoldItalic => "bold-italic",
+        }
+    }
+}
+
+// PDF base-14 families: every viewer has them, so nothing is embedded
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BaseFont {
+    Helvetica,
+    Times,
+    Courier,
+}
+
+impl BaseFont {
+    pub const NAMES: [&'static str; 3] = ["helvetica", "times", "courier"];
+
+    pub fn from_name(name: &str) -> Result<Self, anyhow::Error> {
+        match name.to_ascii_lowercase().as_str() {
+            "helvetica" => Ok(BaseFont::Helvetica),
+            "times" => Ok(BaseFont::Times),
+            "courier" => Ok(BaseFont::Courier),
+            _ => Err(PortformError::Validation {
+                field: "font".to_string(),
+                value: Some(name.to_string()),
+                message: format!("available built-in fonts are {}", Self::NAMES.join(", ")),
+            })?,
+        }
+    }
+
+    fn face(self, style: FontStyle) -> BuiltinFont {
+        match (self, style) {
+            (BaseFont::Helvetica, FontStyle::Regular) => BuiltinFont::Helvetica,
+            (BaseFont::Helvetica, FontStyle::Bold) => BuiltinFont::HelveticaBold,
+            (BaseFont::Helvetica, FontStyle::Italic) => BuiltinFont::HelveticaOblique,
+            (BaseFont::Helvetica, FontStyle::BoldItalic) => BuiltinFont::HelveticaBoldOblique,
+            (BaseFont::Times, FontStyle::Regular) => BuiltinFont::TimesRoman,
+            (BaseFont::Times, FontStyle::Bold) => BuiltinFont::TimesBold,
+            (BaseFont::Times, FontStyle::Italic) => BuiltinFont::TimesItalic,
+            (BaseFont::Times, FontStyle::BoldItalic) => BuiltinFont::TimesBoldItalic,
+            (BaseFont::Courier, FontStyle::Regular) => BuiltinFont::Courier,
+            (BaseFont::Courier, FontStyle::Bold) => BuiltinFont::CourierBold,
+            (BaseFont::Courier, FontStyle::Italic) => BuiltinFont::CourierOblique,
+            (BaseFont::Courier, FontStyle::BoldItalic) => BuiltinFont::CourierBoldOblique,
+        }
+    }
+}
+
+pub struct Face {
+    pub font: IndirectFontRef,
+    pub metrics: FontMetrics,
+}
+
+// A face to write with, plus whatever the renderer must simulate because the family lacks it
+pub struct Resolved<'a> {
+    pub face: &'a Face,
+    pub synthetic_bold: bool,
+    pub synthetic_italic: bool,
+}
+
+// The faces of one font family added to a document, keyed by style
+pub struct FontRegistry {
+    faces: HashMap<FontStyle, Face>,
+}
+
+impl FontRegistry {
+    /// Add every face of `family` to `doc`, using the embedded font for a missing regular face
+    pub fn load(doc: &PdfDocumentReference, family: &FontFamily) -> Result<Self, anyhow::Error> {
+        let mut faces = HashMap::with_capacity(4);
+
+        let sources = [
+            (FontStyle::Regular, &family.regular),
+            (FontStyle::Bold, &family.bold),
+            (FontStyle::Italic, &family.italic),
+            (FontStyle::BoldItalic, &family.bold_italic),
+        ];
+
+        for (style, source) in sources {
+            let bytes = match source {
+                Some(path) => read_font_file(Path::new(path), style)?,
+                None if style == FontStyle::Regular => EMBEDDED_REGULAR.to_vec(),
+                None => continue,
+            };
+
+            let font = doc
+                .add_external_font(bytes.as_slice())
+                .map_err(|e| match source {
+                    Some(path) => anyhow::Error::new(PortformError::FontLoad {
+                        style: style.label(),
+                        path: path.into(),
+                        reason: format!("unable to embed it: {}", e),
+                    }),
+                    None => anyhow!("Unable to embed the {} font: {}", style.label(), e),
+                })?;
+            let metrics = FontMetrics::from_bytes(bytes)?;
+
+            faces.insert(style, Face { font, metrics });
+        }
+
+        Ok(Self { faces })
+    }
+
+    // Reference all four faces of a base-14 family, which every style resolves to exactly
+    pub fn builtin(doc: &PdfDocumentReference, family: BaseFont) -> Result<Self, anyhow::Error> {
+        let mut faces = HashMap::with_capacity(4);
+
+        for style in [
+            FontStyle::Regular,
+            FontStyle::Bold,
+            FontStyle::Italic,
+            FontStyle::BoldItalic,
+        ] {
+            let builtin = family.face(style);
+            let font = doc
+                .add_builtin_font(builtin)
+                .map_err(|e| anyhow!("Unable to add the {} font: {}", style.label(), e))?;
+
+            faces.insert(
+                style,
+                Face {
+                    font,
+                    metrics: FontMetrics::Builtin(builtin),
+                },
+            );
+        }
+
+        Ok(Self { faces })
+    }
+
+    pub fn regular(&self) -> &Face {
+        &self.faces[&FontStyle::Regular]
+    }
+
+    /*
+       Find the closest available face for `style`.
+       Bold-italic falls back to bold, then italic, then regular;
+       any weight or slant lost along the way is flagged for simulation.
+    */
+    pub fn resolve(&self, style: FontStyle) -> Resolved<'_> {
+        let candidates = match style {
+            FontStyle::Regular => vec![FontStyle::Regular],
+            FontStyle::Bold => vec![FontStyle::Bold, FontStyle::Regular],
+            FontStyle::Italic => vec![FontStyle::Italic, FontStyle::Regular],
+            FontStyle::BoldItalic => vec![
+                FontStyle::BoldItalic,
+                FontStyle::Bold,
+                FontStyle::Italic,
+                FontStyle::Regular,
+            ],
+        };
+
+        let (found, face) = candidates
+            .into_iter()
+            .find_map(|candidate| self.faces.get(&candidate).map(|face| (candidate, face)))
+            .unwrap_or((FontStyle::Regular, self.regular()));
+
+        Resolved {
+            face,
+            synthetic_bold: style.is_bold() && !found.is_bold(),
+            synthetic_italic: style.is_italic() && !found.is_italic(),
+        }
+    }
+}
+
+/*
+   Read and parse a TrueType font file, so a bad path is reported when it is configured.
+   OpenType fonts with CFF outlines (usually .otf) cannot be embedded and are rejected here.
+*/
+pub fn read_font_file(path: &Path, style: FontStyle) -> Result<Vec<u8>, anyhow::Error> {
+    let font_error = |reason: String| PortformError::FontLoad {
+        style: style.label(),
+        path: path.to_path_buf(),
+        reason,
+    };
+
+    let bytes = std::fs::read(path).map_err(|e| font_error(e.to_string()))?;
+
+    let metrics = FontMetrics::from_bytes(bytes.clone())
+        .map_err(|e| font_error(format!("not a valid TrueType file: {}", e)))?;
+
+    if !metrics.has_glyf_outlines() {
+        Err(font_error(
+            "it has CFF (PostScript) outlines, but only TrueType outlines can be embedded. \
+             Use the .ttf version of the font"
+                .to_string(),
+        ))?;
+    }
+
+    Ok(bytes)
+}
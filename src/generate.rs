@@ -1,112 +1,611 @@
-use std::fs::File;
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::Path;
 
 use printpdf::*;
 
-use super::consts;
+use super::api::{DocumentShape, FontFamily};
+use super::errors::PortformError;
+use super::file_io::ConfigFileHandler;
+use super::fonts::{FontRegistry, FontStyle};
+use super::layout::{Layout, Paragraph, TextStyle};
+use super::links::{self, Link, LinkTarget};
+use super::metadata::Metadata;
+use super::sections::{self, Entry};
+use super::theme::{Align, ColumnLayout, DividerKind, SectionKind, Theme, ThemeColor};
+
+// Horizontal shear applied to text when an italic face has to be simulated
+const ITALIC_SKEW: f64 = 0.21;
+
+// A vertical strip of the page with its own cursor, so sidebars paginate independently
+#[derive(Debug, Clone, Copy)]
+struct Column {
+    left: f64,
+    width: f64,
+    // Top (mm from page bottom) of the next line to be written
+    cursor: f64,
+    page: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum LineRole {
+    Name,
+    Profession,
+    Heading,
+    Title,
+    Meta,
+    Body,
+}
 
-#[allow(dead_code)]
 pub struct ResumeWriter {
     pub doc: (PdfDocumentReference, PdfPageIndex, PdfLayerIndex),
-    pages: Option<Vec<PdfPage>>,
-    fonts: Vec<IndirectFontRef>,
+    pages: Vec<(PdfPageIndex, PdfLayerIndex)>,
+    fonts: Option<FontRegistry>,
+    theme: Theme,
+    columns: Vec<Column>,
+    active: usize,
+    // Section being written, for per-section style overrides
+    section: Option<SectionKind>,
+    page_numbers: bool,
+    // Characters the selected built-in font has no code for, reported on save
+    unencodable: BTreeSet<char>,
+    links: Vec<Link>,
+    metadata: Option<Metadata>,
 }
 
 impl ResumeWriter {
-    pub fn new(title: String) -> Self {
-        Self {
-            doc: PdfDocument::new(&title, Mm(consts::PAGE_X), Mm(consts::PAGE_Y), "L1"),
-            pages: None,
-            fonts: Vec::with_capacity(2),
-        }
-    }
+    pub fn new(title: String, theme: Theme) -> Self {
+        let page = theme.page;
+        let doc = PdfDocument::new(&title, Mm(page.width), Mm(page.height), "L1");
+
+        let mut writer = Self {
+            pages: vec![(doc.1, doc.2)],
+            doc,
+            fonts: None,
+            theme,
+            columns: Vec::with_capacity(2),
+            active: 0,
+            section: None,
+            page_numbers: false,
+            unencodable: BTreeSet::new(),
+            links: Vec::new(),
+            metadata: None,
+        };
 
-    pub fn load_fonts(mut self) -> Self {
-        let lucon = self
-            .doc
-            .0
-            .add_external_font(File::open("assets/fonts/lucon.ttf").unwrap())
-            .unwrap()
-            .to_owned();
+        writer.reset_columns();
+        writer
+    }
 
-        self.fonts.push(lucon);
+    pub fn with_page_numbers(mut self, enabled: bool) -> Self {
+        self.page_numbers = enabled;
+        self
+    }
 
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        let (doc, page, layer) = self.doc;
+        self.doc = (metadata.apply(doc), page, layer);
+        self.metadata = Some(metadata);
         self
     }
 
+    /*
+       Use the theme's base-14 font when it names one,
+       otherwise embed the configured font family, falling back to the bundled font
+    */
+    pub fn load_fonts(mut self, family: &FontFamily) -> Result<Self, anyhow::Error> {
+        let registry = match self.theme.font {
+            Some(base) => FontRegistry::builtin(&self.doc.0, base)?,
+            None => FontRegistry::load(&self.doc.0, family)?,
+        };
+
+        self.fonts = Some(registry);
+        Ok(self)
+    }
+
+    pub fn save(self, output: &Path) -> Result<(), anyhow::Error> {
+        let pdfa = self.metadata.as_ref().is_some_and(|m| m.pdfa);
+        if pdfa && self.theme.font.is_some() {
+            Err(PortformError::validation(
+                "--pdfa",
+                "PDF/A requires embedded fonts, so it cannot be combined with a built-in font",
+            ))?;
+        }
+
+        if !self.unencodable.is_empty() {
+            let chars: Vec<String> = self
+                .unencodable
+                .iter()
+                .map(|c| format!("{:?}", c))
+                .collect();
+            Err(PortformError::validation(
+                "font",
+                format!(
+                    "the built-in font cannot encode these characters: {}. \
+                     Remove them or use an embedded font (`portform set fonts`).",
+                    chars.join(", ")
+                ),
+            ))?;
+        }
+
+        if self.page_numbers {
+            self.write_page_numbers();
+        }
+
+        let mut pdf = lopdf::Document::load_mem(&self.doc.0.save_to_bytes()?)?;
+        links::annotate(&mut pdf, &self.links)?;
+
+        if let Some(metadata) = &self.metadata {
+            metadata.embed(&mut pdf)?;
+        }
+
+        // The file is only created once the document is known to be valid
+        let mut target = ConfigFileHandler::init_write_file(output)?;
+        Metadata::serialize(&mut pdf)
+            .and_then(|bytes| target.write_all(&bytes))
+            .and_then(|_| target.flush())
+            .map_err(|e| PortformError::output_write(output, e))?;
+
+        Ok(())
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
     pub fn get_primary_font(&self) -> IndirectFontRef {
-        self.fonts[0].clone()
+        self.fonts().regular().font.clone()
     }
 
-    pub fn header_section(
-        layer: PdfLayerReference,
-        font: IndirectFontRef,
-        name: String,
-        profession: String,
-    ) {
-        layer.begin_text_section();
+    fn fonts(&self) -> &FontRegistry {
+        self.fonts
+            .as_ref()
+            .expect("load_fonts must be called before writing")
+    }
 
-        let _blue = color::Color::Rgb(Rgb::new(0.0, 0.0, 200.0, None));
-        layer.set_font(&font, 24.0);
-        layer.set_text_cursor(Mm(10.0), Mm(285.0));
-        layer.set_line_height(20.0);
-        layer.set_character_spacing(3.0);
-        layer.set_text_rendering_mode(TextRenderingMode::Stroke);
-        layer.write_text(name.clone(), &font);
+    pub fn header_section(&mut self, name: String, profession: String) {
+        self.write_line(&name, LineRole::Name);
+        self.write_line(&profession, LineRole::Profession);
+    }
 
-        layer.set_font(&font, 13.0);
-        layer.add_line_break();
-        layer.set_line_offset(5.0);
-        layer.set_text_rendering_mode(TextRenderingMode::Stroke);
-        layer.write_text(profession.clone(), &font);
-        layer.add_line_break();
+    /*
+       Write every section present in the document below the header,
+       in the order and columns given by the theme
+    */
+    pub fn body_sections(&mut self, document: &DocumentShape) {
+        self.split_columns();
 
-        layer.end_text_section();
+        let sidebar = self.theme.sidebar().to_vec();
+        let main = self.theme.sections.clone();
+
+        for (column, sections) in [(0, sidebar), (self.columns.len() - 1, main)] {
+            self.active = column;
+
+            for kind in sections {
+                self.document_section(kind, document);
+            }
+        }
     }
 
-    pub fn contact_section(
-        layer: PdfLayerReference,
-        font: IndirectFontRef,
-        data: (String, String, String, String),
-    ) {
-        let (email, website, phone, address) = data;
+    pub fn document_section(&mut self, kind: SectionKind, document: &DocumentShape) {
+        if let Some(entries) = sections::entries(kind, document, &self.theme) {
+            self.text_block(kind, entries);
+        }
+    }
 
-        layer.begin_text_section();
+    /*
+       Write a heading followed by its entries.
+       Each entry is kept together on one page unless it is taller than a whole page,
+       and the heading is never left at the bottom of a page without its first entry.
+    */
+    fn text_block(&mut self, kind: SectionKind, entries: Vec<Entry>) {
+        self.section = Some(kind);
 
-        layer.set_line_height(10.0);
-        layer.set_character_spacing(1.0);
-        layer.set_text_rendering_mode(TextRenderingMode::Stroke);
+        let body_style = self.style(LineRole::Body).0;
+        let width = self.column().width;
+        let line_height = body_style.line_height_mm();
+        let fonts = self.fonts();
 
-        layer.set_font(&font, 10.0);
-        layer.set_text_cursor(Mm(10.0), Mm(270.0));
-        layer.write_text("CONTACT DETAILS", &font);
+        let wrapped: Vec<Vec<(LineRole, String)>> = entries
+            .iter()
+            .map(|entry| {
+                let roles = entry
+                    .title
+                    .iter()
+                    .map(|text| (LineRole::Title, text))
+                    .chain(entry.meta.iter().map(|text| (LineRole::Meta, text)))
+                    .chain(entry.body.iter().map(|text| (LineRole::Body, text)));
 
-        layer.set_line_height(11.0);
+                roles
+                    .flat_map(|(role, text)| {
+                        // Bold and italic faces are wider, so wrap with the face each line is set in
+                        let face = fonts.resolve(self.style(role).2).face;
+                        Layout::wrap(&face.metrics, text, body_style, width)
+                            .lines
+                            .into_iter()
+                            .map(move |line| (role, line))
+                    })
+                    .collect()
+            })
+            .collect();
 
-        let underline = Line {
-            points: vec![(Point::new(Mm(160.0), Mm(275.0)), true)],
-            is_closed: true,
-            has_fill: true,
+        let height = |entry: &Vec<(LineRole, String)>| {
+            Paragraph {
+                lines: entry.iter().map(|(_, line)| line.clone()).collect(),
+                line_height,
+            }
+            .height()
+        };
+
+        let page_height = self.page_body_height();
+        let first_entry = wrapped.first().map_or(0.0, height);
+        let heading_height = self.theme.heading.text.line_height_mm() + self.divider_height();
+
+        self.ensure_space(heading_height + first_entry.min(page_height - heading_height));
+        self.section_heading(kind);
+
+        for (i, entry) in wrapped.iter().enumerate() {
+            if i > 0 {
+                self.column_mut().cursor -= self.theme.entry_gap;
+            }
+
+            if height(entry) <= page_height {
+                self.ensure_space(height(entry));
+            }
+
+            for (role, line) in entry {
+                self.write_linked_line(line, *role, &entries[i].links);
+            }
+        }
+
+        self.column_mut().cursor -= self.theme.section_gap;
+        self.section = None;
+    }
+
+    fn section_heading(&mut self, kind: SectionKind) {
+        let heading = self.theme.section_title(kind);
+        self.write_line(&heading, LineRole::Heading);
+
+        let divider = self.theme.divider.clone();
+        if divider.kind == DividerKind::None {
+            return;
+        }
+
+        let column = self.column();
+        let y = column.cursor - divider.thickness / 2.0;
+        let width = match divider.kind {
+            DividerKind::Bar => column.width.min(12.0),
+            _ => column.width,
+        };
+
+        let layer = self.layer(column.page);
+        layer.set_outline_color(self.theme.colors.accent.to_pdf());
+        layer.set_outline_thickness(Mm(divider.thickness).into_pt().0);
+        layer.add_shape(Line {
+            points: vec![
+                (Point::new(Mm(column.left), Mm(y)), false),
+                (Point::new(Mm(column.left + width), Mm(y)), false),
+            ],
+            is_closed: false,
+            has_fill: false,
             has_stroke: true,
             is_clipping_path: false,
+        });
+
+        self.column_mut().cursor -= self.divider_height();
+    }
+
+    fn divider_height(&self) -> f64 {
+        match self.theme.divider.kind {
+            DividerKind::None => 0.0,
+            _ => self.theme.divider.thickness + 1.5,
+        }
+    }
+
+    fn style(&self, role: LineRole) -> (TextStyle, ThemeColor, FontStyle) {
+        let theme = &self.theme;
+        let overrides = self
+            .section
+            .and_then(|kind| theme.section_styles.get(&kind))
+            .cloned()
+            .unwrap_or_default();
+
+        let (style, color, font_style) = match role {
+            LineRole::Name => (theme.header.name, theme.colors.accent, FontStyle::Bold),
+            LineRole::Profession => (
+                theme.header.profession,
+                theme.colors.text,
+                FontStyle::Regular,
+            ),
+            LineRole::Heading => (
+                theme.heading.text,
+                theme.colors.heading,
+                FontStyle::new(theme.heading.bold, false),
+            ),
+            LineRole::Title => (theme.body, theme.colors.text, FontStyle::Bold),
+            LineRole::Meta => (theme.body, theme.colors.muted, FontStyle::Italic),
+            LineRole::Body => (theme.body, theme.colors.text, FontStyle::Regular),
         };
 
-        layer.set_fill_color(color::Color::Rgb(Rgb::new(0.0, 0.0, 210.0, None)));
-        layer.add_shape(underline);
+        match role {
+            LineRole::Heading => (style, overrides.heading_color.unwrap_or(color), font_style),
+            LineRole::Title | LineRole::Meta | LineRole::Body => {
+                (overrides.body.unwrap_or(style), color, font_style)
+            }
+            LineRole::Name | LineRole::Profession => (style, color, font_style),
+        }
+    }
 
-        layer.add_line_break();
-        layer.set_font(&font, 9.0);
-        layer.write_text(format!("EMAIL:      {}", &email), &font);
+    // Write a single line at the cursor, breaking to a new page first if it would not fit
+    fn write_line(&mut self, text: &str, role: LineRole) {
+        self.write_linked_line(text, role, &[]);
+    }
+
+    /*
+       Write a line in which any visible link text becomes clickable.
+       Each link run is drawn in the theme's link style and its bounds recorded,
+       so an annotation can be placed over it once the document is saved.
+    */
+    fn write_linked_line(&mut self, text: &str, role: LineRole, targets: &[LinkTarget]) {
+        let (style, color, font_style) = self.style(role);
+        let height = style.line_height_mm();
+        self.ensure_space(height);
+
+        let unsupported = self
+            .fonts()
+            .resolve(font_style)
+            .face
+            .metrics
+            .unsupported(text);
+        self.unencodable.extend(unsupported);
+
+        let resolved = self.fonts().resolve(font_style);
+        let font = resolved.face.font.clone();
+        let measure = |s: &str| {
+            resolved
+                .face
+                .metrics
+                .text_width(s, style.size, style.char_spacing)
+        };
+
+        let column = self.column();
+        let layer = self.layer(column.page);
+
+        let mut x = match (role, self.theme.header.align) {
+            (LineRole::Name | LineRole::Profession, Align::Center) => {
+                column.left + (column.width - measure(text)).max(0.0) / 2.0
+            }
+            _ => column.left,
+        };
+
+        // The cursor marks the top of the line; text sits on the baseline below it
+        let baseline = column.cursor - Mm::from(Pt(style.size)).0;
+        let descent = Mm::from(Pt(style.size * 0.25)).0;
+        let link_style = self.theme.links;
+        let mut links = Vec::new();
+
+        for (span, uri) in links::spans(text, targets) {
+            let color = match uri {
+                Some(_) => link_style.color.unwrap_or(color),
+                None => color,
+            };
+
+            layer.begin_text_section();
+            layer.set_fill_color(color.to_pdf());
+            layer.set_font(&font, style.size);
+            layer.set_character_spacing(style.char_spacing);
+
+            // The family has no bold face: stroke the glyph outlines as well as filling them
+            if resolved.synthetic_bold {
+                layer.set_outline_color(color.to_pdf());
+                layer.set_outline_thickness(style.size / 40.0);
+                layer.set_text_rendering_mode(TextRenderingMode::FillStroke);
+            } else {
+                layer.set_text_rendering_mode(TextRenderingMode::Fill);
+            }
+
+            // Likewise without an italic face, slant the upright glyphs by about 12 degrees
+            if resolved.synthetic_italic {
+                let (x, y) = (Mm(x).into_pt().0, Mm(baseline).into_pt().0);
+                layer.set_text_matrix(TextMatrix::Raw([1.0, 0.0, ITALIC_SKEW, 1.0, x, y]));
+            } else {
+                layer.set_text_cursor(Mm(x), Mm(baseline));
+            }
+
+            layer.write_text(span, &font);
+            layer.end_text_section();
+
+            let width = measure(span);
+
+            if let Some(uri) = uri {
+                if link_style.underline {
+                    let y = baseline - descent / 2.0;
+                    layer.set_outline_color(color.to_pdf());
+                    layer.set_outline_thickness(style.size / 20.0);
+                    layer.add_shape(Line {
+                        points: vec![
+                            (Point::new(Mm(x), Mm(y)), false),
+                            (Point::new(Mm(x + width), Mm(y)), false),
+                        ],
+                        is_closed: false,
+                        has_fill: false,
+                        has_stroke: true,
+                        is_clipping_path: false,
+                    });
+                }
+
+                links.push(Link {
+                    page: column.page,
+                    rect: [
+                        Mm(x).into_pt().0,
+                        Mm(baseline - descent).into_pt().0,
+                        Mm(x + width).into_pt().0,
+                        Mm(column.cursor).into_pt().0,
+                    ],
+                    uri: uri.to_string(),
+                });
+            }
+
+            x += width;
+        }
+
+        self.links.extend(links);
+        self.column_mut().cursor -= height;
+    }
+
+    fn ensure_space(&mut self, height: f64) {
+        if self.column().cursor - height < self.theme.margins.bottom {
+            self.next_page();
+        }
+    }
+
+    // Move the active column to the top of the following page, adding one if needed
+    fn next_page(&mut self) {
+        let page = self.column().page + 1;
+
+        if page == self.pages.len() {
+            self.new_page();
+        }
+
+        let top = self.theme.page.height - self.theme.margins.top;
+        let column = self.column_mut();
+        column.page = page;
+        column.cursor = top;
+    }
+
+    fn new_page(&mut self) {
+        let layer_name = format!("L{}", self.pages.len() + 1);
+        let page = self.doc.0.add_page(
+            Mm(self.theme.page.width),
+            Mm(self.theme.page.height),
+            layer_name,
+        );
+
+        self.pages.push(page);
 
-        layer.add_line_break();
-        layer.write_text(format!("PORTFOLIO:  {}", &website), &font);
+        if self.columns.len() > 1 {
+            let top = self.theme.page.height - self.theme.margins.top;
+            self.fill_sidebar(self.pages.len() - 1, top);
+        }
+    }
+
+    // Full-width column at the top of the first page, used for the header
+    fn reset_columns(&mut self) {
+        let (margins, page) = (self.theme.margins, self.theme.page);
+
+        self.columns = vec![Column {
+            left: margins.left,
+            width: margins.content_width(page.width),
+            cursor: page.height - margins.top,
+            page: 0,
+        }];
+        self.active = 0;
+    }
+
+    // Divide the page below the header into sidebar and main columns for two-column themes
+    fn split_columns(&mut self) {
+        let ColumnLayout::TwoColumn {
+            sidebar_width,
+            gutter,
+            ..
+        } = self.theme.layout
+        else {
+            return;
+        };
+
+        let full = self.columns[0];
 
-        layer.add_line_break();
-        layer.write_text(format!("PHONE #:    {}", phone), &font);
+        let sidebar = Column {
+            width: sidebar_width,
+            ..full
+        };
+        let main = Column {
+            left: full.left + sidebar_width + gutter,
+            width: full.width - sidebar_width - gutter,
+            ..full
+        };
+
+        self.columns = vec![sidebar, main];
+        self.active = 1;
+
+        self.fill_sidebar(full.page, full.cursor);
+    }
+
+    fn fill_sidebar(&self, page: usize, top: f64) {
+        let ColumnLayout::TwoColumn {
+            sidebar_width,
+            sidebar_fill: Some(fill),
+            ..
+        } = self.theme.layout
+        else {
+            return;
+        };
 
-        layer.add_line_break();
-        layer.write_text(format!("LOCATION:   {}", address), &font);
+        // Bleed the background slightly past the text on every side
+        let pad = 3.0;
+        let left = self.theme.margins.left - pad;
+        let right = self.theme.margins.left + sidebar_width + pad;
+        let bottom = self.theme.margins.bottom - pad;
+        let top = top + pad;
 
-        layer.end_text_section();
+        let layer = self.layer(page);
+        layer.set_fill_color(fill.to_pdf());
+        layer.add_shape(Line {
+            points: vec![
+                (Point::new(Mm(left), Mm(bottom)), false),
+                (Point::new(Mm(right), Mm(bottom)), false),
+                (Point::new(Mm(right), Mm(top)), false),
+                (Point::new(Mm(left), Mm(top)), false),
+            ],
+            is_closed: true,
+            has_fill: true,
+            has_stroke: false,
+            is_clipping_path: false,
+        });
+    }
+
+    fn column(&self) -> Column {
+        self.columns[self.active]
+    }
+
+    fn column_mut(&mut self) -> &mut Column {
+        &mut self.columns[self.active]
+    }
+
+    fn layer(&self, page: usize) -> PdfLayerReference {
+        let (page, layer) = self.pages[page];
+        self.doc.0.get_page(page).get_layer(layer)
+    }
+
+    fn page_body_height(&self) -> f64 {
+        self.theme.page.height - self.theme.margins.top - self.theme.margins.bottom
+    }
+
+    // Centered "Page N of M" footer inside the bottom margin of every page
+    fn write_page_numbers(&self) {
+        let font = self.get_primary_font();
+        let style = self.theme.footer;
+        let total = self.pages.len();
+        let baseline = self.theme.margins.bottom / 2.0;
+
+        for page in 0..total {
+            let text = format!("Page {} of {}", page + 1, total);
+            let width =
+                self.fonts()
+                    .regular()
+                    .metrics
+                    .text_width(&text, style.size, style.char_spacing);
+            let x = (self.theme.page.width - width) / 2.0;
+
+            let layer = self.layer(page);
+            layer.begin_text_section();
+            layer.set_fill_color(self.theme.colors.muted.to_pdf());
+            layer.set_font(&font, style.size);
+            layer.set_character_spacing(style.char_spacing);
+            layer.set_text_rendering_mode(TextRenderingMode::Fill);
+            layer.set_text_cursor(Mm(x), Mm(baseline));
+            layer.write_text(text, &font);
+            layer.end_text_section();
+        }
     }
 }
 
@@ -0,0 +1,446 @@
+use std::fmt::Write;
+
+use super::api::DocumentShape;
+use super::fonts::BaseFont;
+use super::links::{self, LinkTarget};
+use super::sections::{self, Entry};
+use super::theme::{Align, ColumnLayout, DividerKind, SectionKind, Theme};
+
+const PERSON: &str = "https://schema.org/Person";
+
+/*
+   Render a self-contained HTML5 page with schema.org Person microdata.
+   Sections and their entries come from the same view as the PDF and text renderings,
+   and the theme's colors, sizes and per-section overrides become CSS.
+*/
+pub fn render(document: &DocumentShape, theme: &Theme) -> String {
+    let (aside, main): (Vec<_>, Vec<_>) = sections::visible(theme, document)
+        .into_iter()
+        .partition(|(kind, _)| theme.sidebar().contains(kind));
+
+    let render_all = |sections: &[(SectionKind, Vec<Entry>)]| -> String {
+        sections
+            .iter()
+            .map(|(kind, entries)| section(*kind, entries, theme))
+            .collect()
+    };
+
+    let header = document.header.clone().unwrap_or_default();
+    let title = match document.title.as_deref() {
+        Some(title) if !title.is_empty() => title.to_string(),
+        _ => header.name.clone(),
+    };
+
+    let mut body = String::new();
+    let _ = write!(
+        body,
+        "<header>\n<h1 itemprop=\"name\">{}</h1>\n<p class=\"profession\" itemprop=\"jobTitle\">{}</p>\n</header>\n",
+        escape(&header.name),
+        escape(&header.profession)
+    );
+
+    if !aside.is_empty() {
+        let _ = write!(
+            body,
+            "<div class=\"columns\">\n<aside>\n{}</aside>\n",
+            render_all(&aside)
+        );
+    }
+
+    let _ = write!(body, "<main>\n{}</main>\n", render_all(&main));
+
+    if !aside.is_empty() {
+        body.push_str("</div>\n");
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <meta name=\"author\" content=\"{author}\">\n<title>{title}</title>\n\
+         <style>\n{css}</style>\n</head>\n\
+         <body itemscope itemtype=\"{person}\">\n{body}</body>\n</html>\n",
+        author = escape(&header.name),
+        title = escape(&title),
+        css = stylesheet(theme),
+        person = PERSON,
+        body = body,
+    )
+}
+
+/// Escape text for use in HTML or XML content and attribute values
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn section(kind: SectionKind, entries: &[Entry], theme: &Theme) -> String {
+    let entries: String = entries
+        .iter()
+        .map(|entry| self::entry(kind, entry))
+        .collect();
+
+    format!(
+        "<section class=\"{}\">\n<h2>{}</h2>\n{}</section>\n",
+        kind.key(),
+        escape(&theme.section_title(kind)),
+        entries
+    )
+}
+
+// Microdata for an entry: its item scope, and the property its body text fills
+fn microdata(kind: SectionKind) -> (&'static str, &'static str) {
+    match kind {
+        SectionKind::Employment => (
+            "itemprop=\"hasOccupation\" itemscope itemtype=\"https://schema.org/Occupation\"",
+            "description",
+        ),
+        SectionKind::Education => (
+            "itemprop=\"alumniOf\" itemscope itemtype=\"https://schema.org/EducationalOrganization\"",
+            "description",
+        ),
+        SectionKind::Certifications => (
+            "itemprop=\"hasCredential\" itemscope itemtype=\"https://schema.org/EducationalOccupationalCredential\"",
+            "",
+        ),
+        SectionKind::Summary => ("", "description"),
+        SectionKind::Skills => ("", "knowsAbout"),
+        SectionKind::Contact | SectionKind::Projects => ("", ""),
+    }
+}
+
+fn entry(kind: SectionKind, entry: &Entry) -> String {
+    let (scope, prop) = microdata(kind);
+    // Contact links are the person's own email, website and phone
+    let link_props = kind == SectionKind::Contact;
+
+    if entry.is_list() {
+        let items: String = entry
+            .body
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                format!(
+                    "<li>{}</li>\n",
+                    linked(line.trim(), &entry.links, link_props)
+                )
+            })
+            .collect();
+
+        return format!("<ul class=\"{}\">\n{}</ul>\n", kind.key(), items);
+    }
+
+    let title = entry
+        .title
+        .as_ref()
+        .map(|title| {
+            let title = linked(title, &entry.links, false);
+            match scope {
+                "" => format!("<h3>{}</h3>\n", title),
+                _ => format!("<h3><span itemprop=\"name\">{}</span></h3>\n", title),
+            }
+        })
+        .unwrap_or_default();
+    let meta = entry
+        .meta
+        .as_ref()
+        .map(|meta| {
+            format!(
+                "<p class=\"meta\">{}</p>\n",
+                linked(meta, &entry.links, false)
+            )
+        })
+        .unwrap_or_default();
+    let attribute = match prop {
+        "" => String::new(),
+        prop => format!(" itemprop=\"{}\"", prop),
+    };
+
+    // One <p> per non-empty line
+    let body: String = entry
+        .body
+        .iter()
+        .flat_map(|text| text.lines())
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            format!(
+                "<p{}>{}</p>\n",
+                attribute,
+                linked(line, &entry.links, link_props)
+            )
+        })
+        .collect();
+
+    let scope = match scope {
+        "" => String::new(),
+        scope => format!(" {}", scope),
+    };
+
+    format!(
+        "<article class=\"entry\"{}>\n{}{}{}</article>\n",
+        scope, title, meta, body
+    )
+}
+
+// Escape text and turn link text into anchors, tagged as the person's contact points when asked
+fn linked(text: &str, targets: &[LinkTarget], props: bool) -> String {
+    links::spans(text, targets)
+        .into_iter()
+        .map(|(span, uri)| match uri {
+            Some(uri) => {
+                let prop = match uri.split_once(':') {
+                    _ if !props => String::new(),
+                    Some(("mailto", _)) => " itemprop=\"email\"".to_string(),
+                    Some(("tel", _)) => " itemprop=\"telephone\"".to_string(),
+                    _ => " itemprop=\"url\"".to_string(),
+                };
+                format!("<a{} href=\"{}\">{}</a>", prop, escape(uri), escape(span))
+            }
+            None => escape(span),
+        })
+        .collect()
+}
+
+fn stylesheet(theme: &Theme) -> String {
+    let colors = &theme.colors;
+    let color = |c| String::from(c);
+    let link = theme.links.color.unwrap_or(colors.text);
+
+    let font_family = match theme.font {
+        Some(BaseFont::Helvetica) => "Helvetica, Arial, sans-serif",
+        Some(BaseFont::Times) => "\"Times New Roman\", Times, serif",
+        Some(BaseFont::Courier) => "\"Courier New\", Courier, monospace",
+        None => "\"Lucida Console\", Monaco, monospace",
+    };
+
+    let (sidebar_width, gutter, sidebar_fill) = match &theme.layout {
+        ColumnLayout::TwoColumn {
+            sidebar_width,
+            gutter,
+            sidebar_fill,
+            ..
+        } => (*sidebar_width, *gutter, sidebar_fill.map(color)),
+        ColumnLayout::Single => (0.0, 0.0, None),
+    };
+
+    let divider = match theme.divider.kind {
+        DividerKind::None => String::new(),
+        DividerKind::Rule => format!(
+            "h2 {{ border-bottom: {}mm solid var(--accent); padding-bottom: 1mm; }}\n",
+            theme.divider.thickness
+        ),
+        DividerKind::Bar => format!(
+            "h2::after {{ content: \"\"; display: block; width: 12mm; margin-top: 1mm; border-bottom: {}mm solid var(--accent); }}\n",
+            theme.divider.thickness
+        ),
+    };
+
+    let align = match theme.header.align {
+        Align::Left => "left",
+        Align::Center => "center",
+    };
+
+    let mut css = format!(
+        ":root {{\n  --text: {text};\n  --heading: {heading};\n  --accent: {accent};\n  --muted: {muted};\n  --link: {link};\n  --sidebar-fill: {fill};\n}}\n\
+         body {{ margin: 0 auto; max-width: {page}mm; padding: {top}mm {right}mm {bottom}mm {left}mm; box-sizing: border-box; \
+         color: var(--text); font-family: {font}; font-size: {body}pt; line-height: {body_line}pt; }}\n\
+         header {{ text-align: {align}; }}\n\
+         h1 {{ color: var(--accent); font-size: {name}pt; line-height: {name_line}pt; margin: 0; }}\n\
+         .profession {{ font-size: {profession}pt; margin: 0 0 {section_gap}mm; }}\n\
+         h2 {{ color: var(--heading); font-size: {heading_size}pt; font-weight: {heading_weight}; margin: {section_gap}mm 0 2mm; }}\n\
+         {divider}\
+         h3 {{ font-size: 1em; margin: 0; }}\n\
+         .entry {{ margin-bottom: {entry_gap}mm; }}\n\
+         .entry p {{ margin: 0; }}\n\
+         .meta {{ color: var(--muted); font-style: italic; }}\n\
+         ul {{ list-style: none; margin: 0; padding: 0; }}\n\
+         a {{ color: var(--link); text-decoration: {underline}; }}\n",
+        text = color(colors.text),
+        heading = color(colors.heading),
+        accent = color(colors.accent),
+        muted = color(colors.muted),
+        link = color(link),
+        fill = sidebar_fill.clone().unwrap_or_else(|| "transparent".to_string()),
+        page = theme.page.width,
+        top = theme.margins.top,
+        right = theme.margins.right,
+        bottom = theme.margins.bottom,
+        left = theme.margins.left,
+        font = font_family,
+        body = theme.body.size,
+        body_line = theme.body.line_height,
+        align = align,
+        name = theme.header.name.size,
+        name_line = theme.header.name.line_height,
+        profession = theme.header.profession.size,
+        section_gap = theme.section_gap,
+        heading_size = theme.heading.text.size,
+        heading_weight = if theme.heading.bold { "bold" } else { "normal" },
+        divider = divider,
+        entry_gap = theme.entry_gap,
+        underline = if theme.links.underline { "underline" } else { "none" },
+    );
+
+    // Per-section overrides, applied as the PDF applies them: heading color, then body text size
+    for (kind, style) in &theme.section_styles {
+        if let Some(heading) = style.heading_color {
+            let _ = writeln!(
+                css,
+                "section.{} h2 {{ color: {}; }}",
+                kind.key(),
+                color(heading)
+            );
+        }
+        if let Some(body) = style.body {
+            let _ = writeln!(
+                css,
+                "section.{} {{ font-size: {}pt; line-height: {}pt; letter-spacing: {}pt; }}",
+                kind.key(),
+                body.size,
+                body.line_height,
+                body.char_spacing
+            );
+        }
+    }
+
+    if sidebar_width > 0.0 {
+        let _ = write!(
+            css,
+            ".columns {{ display: grid; grid-template-columns: {}mm 1fr; gap: {}mm; }}\n\
+             aside {{ background: var(--sidebar-fill); padding: 0 2mm; }}\n\
+             @media (max-width: 600px) {{ .columns {{ display: block; }} }}\n",
+            sidebar_width, gutter
+        );
+    }
+
+    // Print on the theme's page size with its margins, and let entries avoid page breaks
+    let _ = write!(
+        css,
+        "@media print {{\n  @page {{ size: {}mm {}mm; margin: {}mm {}mm {}mm {}mm; }}\n  \
+         body {{ max-width: none; padding: 0; }}\n  \
+         .entry, h2 {{ break-inside: avoid; }}\n  h2 {{ break-after: avoid; }}\n  \
+         a {{ color: inherit; text-decoration: none; }}\n  \
+         aside {{ print-color-adjust: exact; -webkit-print-color-adjust: exact; }}\n}}\n",
+        theme.page.width,
+        theme.page.height,
+        theme.margins.top,
+        theme.margins.right,
+        theme.margins.bottom,
+        theme.margins.left
+    );
+
+    css
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{ContactDetails, Header, Project, Summary};
+
+    fn with_deployment(deployment: &str) -> String {
+        let document = DocumentShape {
+            projects: Some(vec![Project {
+                name: "Portform".to_string(),
+                description: "Resume generator".to_string(),
+                deployment: deployment.to_string(),
+            }]),
+            ..DocumentShape::default()
+        };
+
+        render(&document, &Theme::default())
+    }
+
+    #[test]
+    fn links_only_web_urls() {
+        assert!(with_deployment("example.com").contains("<a href=\"https://example.com\">"));
+
+        for deployment in [
+            "javascript://%0aalert(document.domain)",
+            "data://text/html,<script>alert(1)</script>",
+            "JavaScript://example.com",
+        ] {
+            let html = with_deployment(deployment);
+            assert!(!html.contains("<a "), "{:?}", deployment);
+            assert!(html.contains(&escape(deployment)), "{:?}", deployment);
+        }
+    }
+
+    fn document() -> DocumentShape {
+        DocumentShape {
+            header: Some(Header {
+                name: "Ada <Byron> & Co".to_string(),
+                profession: "\"Analyst\"".to_string(),
+            }),
+            summary: Some(Summary {
+                body: "Loves <b>math</b>\nand & engines".to_string(),
+            }),
+            contact_details: Some(ContactDetails {
+                email: "ada@example.com".to_string(),
+                website: "example.com".to_string(),
+                phone: "+44 20 7946 0000".to_string(),
+                address: "London".to_string(),
+            }),
+            projects: Some(vec![Project {
+                name: "Notes & Tables".to_string(),
+                description: "A <\"translation\">".to_string(),
+                deployment: String::new(),
+            }]),
+            ..DocumentShape::default()
+        }
+    }
+
+    #[test]
+    fn escapes_names_and_descriptions() {
+        let html = render(&document(), &Theme::default());
+
+        assert!(html.contains("<h1 itemprop=\"name\">Ada &lt;Byron&gt; &amp; Co</h1>"));
+        assert!(html.contains("<title>Ada &lt;Byron&gt; &amp; Co</title>"));
+        assert!(html.contains("itemprop=\"jobTitle\">&quot;Analyst&quot;</p>"));
+        assert!(html.contains("<h3>Notes &amp; Tables</h3>"));
+        assert!(html.contains("<p>A &lt;&quot;translation&quot;&gt;</p>"));
+        assert!(!html.contains("<b>"));
+    }
+
+    #[test]
+    fn marks_up_the_header_and_contact_points() {
+        let html = render(&document(), &Theme::default());
+
+        assert!(html.contains("<body itemscope itemtype=\"https://schema.org/Person\">"));
+        assert!(html.contains("itemprop=\"jobTitle\""));
+        assert!(html
+            .contains("<a itemprop=\"email\" href=\"mailto:ada@example.com\">ada@example.com</a>"));
+        assert!(html.contains("<a itemprop=\"url\" href=\"https://example.com\">"));
+        assert!(html.contains("<a itemprop=\"telephone\" href=\"tel:+442079460000\">"));
+    }
+
+    #[test]
+    fn splits_sections_between_the_sidebar_and_main() {
+        let theme = Theme::modern();
+        let html = render(&document(), &theme);
+
+        let (aside, main) = html.split_once("</aside>").unwrap();
+        for (kind, _) in sections::visible(&theme, &document()) {
+            let tag = format!("<section class=\"{}\">", kind.key());
+            let in_sidebar = theme.sidebar().contains(&kind);
+            assert_eq!(aside.contains(&tag), in_sidebar, "{:?}", kind);
+            assert_eq!(main.contains(&tag), !in_sidebar, "{:?}", kind);
+        }
+
+        let html = render(&document(), &Theme::classic());
+        assert!(!html.contains("<aside>") && !html.contains("class=\"columns\""));
+    }
+
+    #[test]
+    fn renders_untitled_entries_as_lists_and_the_rest_as_paragraphs() {
+        let html = render(&document(), &Theme::default());
+
+        assert!(html.contains("<ul class=\"contact\">\n<li>EMAIL: "));
+        assert!(html.contains("<li>LOCATION: London</li>\n</ul>"));
+        assert!(html.contains(
+            "<p itemprop=\"description\">Loves &lt;b&gt;math&lt;/b&gt;</p>\n<p itemprop=\"description\">and &amp; engines</p>"
+        ));
+    }
+}
@@ -0,0 +1,609 @@
+use anyhow::bail;
+use serde_json::{json, Map, Value};
+
+use super::api::{
+    Certification, ContactDetails, DocumentShape, EducationEntry, Header, HistoryEntry, Project,
+    Skill, Summary,
+};
+use super::dates::{DateRange, ResumeDate};
+use super::errors::ValidationError;
+use super::validation;
+
+/*
+   Mapping between portform's config and the JSON Resume standard (jsonresume.org).
+   Both directions are lossy, so each returns the paths of fields it had to leave out.
+   Import also returns the contact details that were kept as written because they could not be normalized.
+*/
+pub struct JsonResume;
+
+impl JsonResume {
+    /// Replace the resume content of `document` with a JSON Resume, keeping portform settings
+    pub fn import(
+        resume: Value,
+        document: &mut DocumentShape,
+    ) -> Result<(Vec<String>, Vec<String>), anyhow::Error> {
+        let Value::Object(mut resume) = resume else {
+            bail!("A JSON Resume must be a JSON object");
+        };
+
+        let mut unmapped = Vec::new();
+        let mut basics = take_object(&mut resume, "basics");
+
+        document.header = Some(Header {
+            name: take_string(&mut basics, "name"),
+            profession: take_string(&mut basics, "label"),
+        });
+
+        let summary = take_string(&mut basics, "summary");
+        document.summary = Some(Summary { body: summary }).filter(|s| !s.body.is_empty());
+
+        let mut location = take_object(&mut basics, "location");
+        let address = ["address", "city", "region", "postalCode", "countryCode"]
+            .map(|key| take_string(&mut location, key))
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ");
+        report(&mut unmapped, "basics.location", location);
+
+        // One value that does not normalize, e.g. a phone without a country code, should not stop the import
+        let mut unnormalized = Vec::new();
+        let mut contact = |key: &str, value: String, check: fn(&str) -> Result<String, _>| {
+            check(&value).unwrap_or_else(|error: ValidationError| {
+                let (_, reason) = error.parts();
+                unnormalized.push(format!("{} {:?}: {}", key, value, reason));
+                value
+            })
+        };
+
+        let email = take_string(&mut basics, "email");
+        let website = take_string(&mut basics, "url");
+        let phone = take_string(&mut basics, "phone");
+
+        document.contact_details = Some(ContactDetails {
+            email: contact("basics.email", email, validation::email),
+            website: contact("basics.url", website, validation::website),
+            phone: contact("basics.phone", phone, validation::phone),
+            address: contact("basics.location", address, validation::address),
+        });
+        report(&mut unmapped, "basics", basics);
+
+        document.employment_history = take_list(&mut resume, "work", &mut unmapped, |job| {
+            // The employer has no field of its own, so it is shown where the location is
+            let location = [take_string(job, "name"), take_string(job, "location")]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            HistoryEntry {
+                position: take_string(job, "position"),
+                location,
+                dates_employed: take_dates(job),
+                description: with_highlights(take_string(job, "summary"), job),
+                pinned: false,
+            }
+        });
+
+        document.education = take_list(&mut resume, "education", &mut unmapped, |school| {
+            let degree = [
+                take_string(school, "studyType"),
+                take_string(school, "area"),
+            ]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+            EducationEntry {
+                dates: take_dates(school),
+                name: take_string(school, "institution"),
+                location: take_string(school, "location"),
+                description: degree,
+                pinned: false,
+            }
+        });
+
+        document.projects = take_list(&mut resume, "projects", &mut unmapped, |project| Project {
+            name: take_string(project, "name"),
+            deployment: take_string(project, "url"),
+            description: with_highlights(take_string(project, "description"), project),
+        });
+
+        // Keywords are listed as skills of their own so that they still reach the resume
+        document.skillset = take_list(&mut resume, "skills", &mut unmapped, |skill| {
+            let keywords = take_strings(skill, "keywords");
+            std::iter::once(take_string(skill, "name"))
+                .chain(keywords)
+                .filter(|name| !name.is_empty())
+                .map(|name| Skill { name })
+                .collect::<Vec<_>>()
+        })
+        .map(|groups| groups.into_iter().flatten().collect());
+
+        document.certifications = take_list(&mut resume, "certificates", &mut unmapped, |cert| {
+            Certification {
+                name: take_string(cert, "name"),
+                date_issued: take_date(cert, "date"),
+                pinned: false,
+            }
+        });
+
+        resume.remove("$schema");
+        report(&mut unmapped, "", resume);
+
+        Ok((unmapped, unnormalized))
+    }
+
+    /// Build a JSON Resume from the resume content of `document`
+    pub fn export(document: &DocumentShape) -> (Value, Vec<String>) {
+        let mut unmapped = Vec::new();
+        let header = document.header.clone().unwrap_or_default();
+        let contact = document.contact_details.clone().unwrap_or_default();
+
+        let mut basics = Map::new();
+        insert(&mut basics, "name", &header.name);
+        insert(&mut basics, "label", &header.profession);
+        insert(&mut basics, "email", &contact.email);
+        insert(&mut basics, "phone", &contact.phone);
+        insert(&mut basics, "url", &contact.website);
+
+        if let Some(summary) = &document.summary {
+            insert(&mut basics, "summary", &summary.body);
+        }
+
+        if !contact.address.is_empty() {
+            basics.insert("location".into(), json!({ "address": contact.address }));
+        }
+
+        let mut resume = Map::new();
+        resume.insert(
+            "$schema".into(),
+            json!("https://raw.githubusercontent.com/jsonresume/resume-schema/v1.0.0/schema.json"),
+        );
+        resume.insert("basics".into(), Value::Object(basics));
+
+        let work = export_list(&document.employment_history, |i, job| {
+            let mut work = Map::new();
+            insert(&mut work, "position", &job.position);
+            insert(&mut work, "name", &job.location);
+            insert(&mut work, "summary", &job.description);
+            insert_dates(
+                &mut work,
+                &job.dates_employed,
+                &format!("employment_history[{}]", i),
+                &mut unmapped,
+            );
+            work
+        });
+
+        let education = export_list(&document.education, |i, school| {
+            let path = format!("education[{}]", i);
+            let mut education = Map::new();
+            insert(&mut education, "institution", &school.name);
+            insert(&mut education, "area", &school.description);
+            insert_dates(&mut education, &school.dates, &path, &mut unmapped);
+
+            if !school.location.is_empty() {
+                unmapped.push(format!("{}.location", path));
+            }
+
+            education
+        });
+
+        let projects = export_list(&document.projects, |_, project| {
+            let mut entry = Map::new();
+            insert(&mut entry, "name", &project.name);
+            insert(&mut entry, "description", &project.description);
+            insert(&mut entry, "url", &project.deployment);
+            entry
+        });
+
+        let skills = export_list(&document.skillset, |_, skill| {
+            let mut entry = Map::new();
+            insert(&mut entry, "name", &skill.name);
+            entry
+        });
+
+        let certificates = export_list(&document.certifications, |i, cert| {
+            let mut entry = Map::new();
+            insert(&mut entry, "name", &cert.name);
+
+            let path = format!("certifications[{}].date_issued", i);
+            insert_date(
+                &mut entry,
+                "date",
+                cert.date_issued.as_ref(),
+                &path,
+                &mut unmapped,
+            );
+
+            entry
+        });
+
+        for (key, list) in [
+            ("work", work),
+            ("education", education),
+            ("projects", projects),
+            ("skills", skills),
+            ("certificates", certificates),
+        ] {
+            if !list.is_empty() {
+                resume.insert(key.into(), Value::Array(list));
+            }
+        }
+
+        (Value::Object(resume), unmapped)
+    }
+}
+
+fn take_object(map: &mut Map<String, Value>, key: &str) -> Map<String, Value> {
+    match map.remove(key) {
+        Some(Value::Object(object)) => object,
+        Some(other) => {
+            // Put it back so that it is reported instead of silently dropped
+            map.insert(key.to_string(), other);
+            Map::new()
+        }
+        None => Map::new(),
+    }
+}
+
+fn take_string(map: &mut Map<String, Value>, key: &str) -> String {
+    match map.remove(key) {
+        Some(Value::String(text)) => text.trim().to_string(),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => {
+            map.insert(key.to_string(), other);
+            String::new()
+        }
+    }
+}
+
+fn take_strings(map: &mut Map<String, Value>, key: &str) -> Vec<String> {
+    match map.remove(key) {
+        Some(Value::Array(items)) if items.iter().all(Value::is_string) => items
+            .into_iter()
+            .filter_map(|item| item.as_str().map(|s| s.trim().to_string()))
+            .collect(),
+        Some(Value::Null) | None => Vec::new(),
+        Some(other) => {
+            map.insert(key.to_string(), other);
+            Vec::new()
+        }
+    }
+}
+
+fn take_date(map: &mut Map<String, Value>, key: &str) -> Option<ResumeDate> {
+    Some(take_string(map, key))
+        .filter(|text| !text.is_empty())
+        .map(ResumeDate::lenient)
+}
+
+/*
+   JSON Resume leaves endDate out for a position that is still held.
+   An end before the start is put back so that it is reported instead of saved.
+*/
+fn take_dates(map: &mut Map<String, Value>) -> DateRange {
+    let start = take_date(map, "startDate");
+    let end = match take_date(map, "endDate") {
+        None if start.is_some() => Some(ResumeDate::Present),
+        end => end,
+    };
+
+    DateRange::new(start.clone(), end.clone()).unwrap_or_else(|_| {
+        if let Some(end) = end {
+            map.insert("endDate".to_string(), Value::String(end.to_string()));
+        }
+
+        DateRange { start, end: None }
+    })
+}
+
+// Highlights become bullet lines below the summary
+fn with_highlights(summary: String, map: &mut Map<String, Value>) -> String {
+    std::iter::once(summary)
+        .chain(
+            take_strings(map, "highlights")
+                .into_iter()
+                .map(|highlight| format!("- {}", highlight)),
+        )
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/*
+   Map every object of a top-level list, reporting leftover fields per item.
+   Returns None for a missing or empty list so the section stays hidden.
+*/
+fn take_list<T>(
+    resume: &mut Map<String, Value>,
+    key: &str,
+    unmapped: &mut Vec<String>,
+    mut map_item: impl FnMut(&mut Map<String, Value>) -> T,
+) -> Option<Vec<T>> {
+    let items = match resume.remove(key) {
+        Some(Value::Array(items)) => items,
+        Some(other) => {
+            resume.insert(key.to_string(), other);
+            return None;
+        }
+        None => return None,
+    };
+
+    let mapped: Vec<T> = items
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, item)| {
+            let path = format!("{}[{}]", key, i);
+            let Value::Object(mut item) = item else {
+                unmapped.push(path);
+                return None;
+            };
+
+            let mapped = map_item(&mut item);
+            report(unmapped, &path, item);
+            Some(mapped)
+        })
+        .collect();
+
+    Some(mapped).filter(|mapped| !mapped.is_empty())
+}
+
+// Record every field left over after mapping, skipping empty values
+fn report(unmapped: &mut Vec<String>, prefix: &str, leftover: Map<String, Value>) {
+    for (key, value) in leftover {
+        let empty = match &value {
+            Value::Null => true,
+            Value::String(s) => s.is_empty(),
+            Value::Array(items) => items.is_empty(),
+            Value::Object(map) => map.is_empty(),
+            _ => false,
+        };
+
+        if !empty {
+            unmapped.push(match prefix {
+                "" => key,
+                prefix => format!("{}.{}", prefix, key),
+            });
+        }
+    }
+}
+
+fn insert(map: &mut Map<String, Value>, key: &str, value: &str) {
+    if !value.is_empty() {
+        map.insert(key.to_string(), Value::String(value.to_string()));
+    }
+}
+
+// JSON Resume dates must be ISO 8601, so free-form dates are reported instead of exported
+fn insert_dates(
+    map: &mut Map<String, Value>,
+    dates: &DateRange,
+    path: &str,
+    unmapped: &mut Vec<String>,
+) {
+    insert_date(
+        map,
+        "startDate",
+        dates.start.as_ref(),
+        &format!("{}.start", path),
+        unmapped,
+    );
+    insert_date(
+        map,
+        "endDate",
+        dates.end.as_ref(),
+        &format!("{}.end", path),
+        unmapped,
+    );
+}
+
+fn insert_date(
+    map: &mut Map<String, Value>,
+    key: &str,
+    date: Option<&ResumeDate>,
+    path: &str,
+    unmapped: &mut Vec<String>,
+) {
+    match date {
+        // A missing end date already means "still ongoing"
+        None | Some(ResumeDate::Present) => {}
+        Some(ResumeDate::Text(_)) => unmapped.push(path.to_string()),
+        Some(date) => insert(map, key, &date.to_string()),
+    }
+}
+
+fn export_list<T>(
+    items: &Option<Vec<T>>,
+    mut map_item: impl FnMut(usize, &T) -> Map<String, Value>,
+) -> Vec<Value> {
+    items
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, item)| Value::Object(map_item(i, item)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The sample resume published on jsonresume.org
+    const SAMPLE: &str = r#"{
+      "basics": {
+        "name": "Richard Hendricks",
+        "label": "Programmer",
+        "image": "",
+        "email": "richard.hendricks@mail.com",
+        "phone": "(912) 555-4321",
+        "url": "http://richardhendricks.example.com",
+        "summary": "Richard hails from Tulsa. He has earned degrees from the University of Oklahoma and Stanford.",
+        "location": {
+          "address": "2712 Broadway St",
+          "postalCode": "CA 94115",
+          "city": "San Francisco",
+          "countryCode": "US",
+          "region": "California"
+        },
+        "profiles": [
+          { "network": "Twitter", "username": "neutralthoughts", "url": "" },
+          { "network": "SoundCloud", "username": "dandymusicnl", "url": "https://soundcloud.example.com/dandymusicnl" }
+        ]
+      },
+      "work": [{
+        "name": "Pied Piper",
+        "location": "Palo Alto, CA",
+        "description": "Awesome compression company",
+        "position": "CEO/President",
+        "url": "http://piedpiper.example.com",
+        "startDate": "2013-12-01",
+        "endDate": "2014-12-01",
+        "summary": "Pied Piper is a multi-platform technology based on a proprietary universal compression algorithm.",
+        "highlights": [
+          "Build an algorithm for artist to detect if their music was violating copy right infringement laws",
+          "Successfully won Techcrunch Disrupt"
+        ]
+      }],
+      "education": [{
+        "institution": "University of Oklahoma",
+        "url": "https://www.ou.edu/",
+        "area": "Information Technology",
+        "studyType": "Bachelor",
+        "startDate": "2011-06-01",
+        "endDate": "2014-01-01",
+        "score": "4.0",
+        "courses": ["DB1101 - Basic SQL", "CS2011 - Java Introduction"]
+      }],
+      "skills": [
+        { "name": "Web Development", "level": "Master", "keywords": ["HTML", "CSS", "Javascript"] },
+        { "name": "Compression", "level": "Master", "keywords": ["Mpeg", "MP4", "GIF"] }
+      ],
+      "languages": [{ "language": "English", "fluency": "Native speaker" }],
+      "interests": [{ "name": "Wildlife", "keywords": ["Ferrets", "Unicorns"] }],
+      "projects": [{
+        "name": "Miss Direction",
+        "description": "A mapping engine that misguides you",
+        "highlights": ["Won award at AIHacks 2016"],
+        "startDate": "2016-08-24",
+        "endDate": "2016-08-24",
+        "url": "missdirection.example.com"
+      }]
+    }"#;
+
+    #[test]
+    fn imports_the_sample_resume() {
+        let mut document = DocumentShape::default();
+        let (unmapped, unnormalized) =
+            JsonResume::import(serde_json::from_str(SAMPLE).unwrap(), &mut document).unwrap();
+
+        let contact = document.contact_details.unwrap();
+        assert_eq!(contact.email, "richard.hendricks@mail.com");
+        assert_eq!(contact.website, "http://richardhendricks.example.com");
+        // No country code, so the number is kept as written and reported
+        assert_eq!(contact.phone, "(912) 555-4321");
+        assert_eq!(unnormalized.len(), 1);
+        assert!(unnormalized[0].starts_with("basics.phone \"(912) 555-4321\""));
+
+        assert_eq!(document.header.unwrap().name, "Richard Hendricks");
+        let jobs = document.employment_history.unwrap();
+        assert_eq!(jobs[0].location, "Pied Piper, Palo Alto, CA");
+        assert_eq!(
+            jobs[0].dates_employed.end,
+            ResumeDate::parse("2014-12-01").ok()
+        );
+        assert_eq!(document.skillset.unwrap().len(), 8);
+        assert_eq!(
+            document.projects.unwrap()[0].deployment,
+            "missdirection.example.com"
+        );
+
+        for path in [
+            "basics.profiles",
+            "work[0].description",
+            "education[0].courses",
+            "languages",
+            "interests",
+            "projects[0].startDate",
+        ] {
+            assert!(unmapped.iter().any(|p| p == path), "{}", path);
+        }
+    }
+
+    #[test]
+    fn exports_basics_and_iso_dates() {
+        let date = |text: &str| Some(ResumeDate::lenient(text.to_string()));
+        let document = DocumentShape {
+            header: Some(Header {
+                name: "Ada Lovelace".to_string(),
+                profession: "Analyst".to_string(),
+            }),
+            contact_details: Some(ContactDetails {
+                email: "ada@example.com".to_string(),
+                address: "London".to_string(),
+                ..ContactDetails::default()
+            }),
+            employment_history: Some(vec![
+                HistoryEntry {
+                    position: "Analyst".to_string(),
+                    location: "Engine Co".to_string(),
+                    dates_employed: DateRange {
+                        start: date("2021-03"),
+                        end: date("Present"),
+                    },
+                    description: String::new(),
+                    pinned: false,
+                },
+                HistoryEntry {
+                    position: "Translator".to_string(),
+                    location: String::new(),
+                    dates_employed: DateRange {
+                        start: date("Spring 1842"),
+                        end: date("1843-09-05"),
+                    },
+                    description: String::new(),
+                    pinned: false,
+                },
+            ]),
+            certifications: Some(vec![Certification {
+                date_issued: date("the year of the notes"),
+                name: "Notes".to_string(),
+                pinned: false,
+            }]),
+            ..DocumentShape::default()
+        };
+
+        let (resume, unmapped) = JsonResume::export(&document);
+
+        assert_eq!(
+            resume["$schema"],
+            "https://raw.githubusercontent.com/jsonresume/resume-schema/v1.0.0/schema.json"
+        );
+        assert_eq!(
+            resume["basics"],
+            json!({
+                "name": "Ada Lovelace",
+                "label": "Analyst",
+                "email": "ada@example.com",
+                "location": { "address": "London" }
+            })
+        );
+
+        // A present end date is left out, as JSON Resume reads a missing one as ongoing
+        assert_eq!(resume["work"][0]["startDate"], "2021-03");
+        assert!(resume["work"][0].get("endDate").is_none());
+        assert!(resume["work"][1].get("startDate").is_none());
+        assert_eq!(resume["work"][1]["endDate"], "1843-09-05");
+        assert!(resume["certificates"][0].get("date").is_none());
+
+        assert_eq!(
+            unmapped,
+            [
+                "employment_history[1].start",
+                "certifications[0].date_issued"
+            ]
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use owned_ttf_parser::{AsFaceRef, OwnedFace, TableName};
+use printpdf::{BuiltinFont, Mm, Pt};
+use serde::{Deserialize, Serialize};
+
+use super::afm;
+use super::consts;
+
+// Horizontal glyph metrics for a font, used to measure text before writing it
+pub enum FontMetrics {
+    TrueType(OwnedFace),
+    // Base-14 fonts carry no font program, so widths come from their AFM tables
+    Builtin(BuiltinFont),
+}
+
+impl FontMetrics {
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, anyhow::Error> {
+        let face = OwnedFace::from_vec(bytes, 0)
+            .map_err(|e| anyhow::anyhow!("Unable to parse font data: {}", e))?;
+
+        Ok(FontMetrics::TrueType(face))
+    }
+
+    /// Whether glyphs are TrueType outlines, the only kind printpdf can embed
+    pub fn has_glyf_outlines(&self) -> bool {
+        match self {
+            FontMetrics::TrueType(face) => face.as_face_ref().has_table(TableName::GlyphData),
+            FontMetrics::Builtin(_) => true,
+        }
+    }
+
+    /// Width of `text` in mm when set at `size` pt with `char_spacing` pt between glyphs
+    pub fn text_width(&self, text: &str, size: f64, char_spacing: f64) -> f64 {
+        // Characters the font cannot show are skipped by `write_text`, so skip them here too
+        let (advance, glyphs, units_per_em) = match self {
+            FontMetrics::TrueType(face) => {
+                let face = face.as_face_ref();
+                let (advance, glyphs) = text
+                    .chars()
+                    .filter_map(|c| face.glyph_index(c))
+                    .map(|glyph| face.glyph_hor_advance(glyph).unwrap_or(0) as f64)
+                    .fold((0.0, 0), |(width, count), advance| {
+                        (width + advance, count + 1)
+                    });
+
+                (advance, glyphs, face.units_per_em().unwrap_or(1000) as f64)
+            }
+            FontMetrics::Builtin(font) => {
+                let (advance, glyphs) = text
+                    .chars()
+                    .filter_map(|c| afm::char_width(*font, c))
+                    .fold((0.0, 0), |(width, count), advance| {
+                        (width + advance as f64, count + 1)
+                    });
+
+                (advance, glyphs, 1000.0)
+            }
+        };
+
+        let points = advance / units_per_em * size + glyphs as f64 * char_spacing;
+
+        Mm::from(Pt(points)).0
+    }
+
+    // Characters of `text` this font cannot write; TrueType faces simply skip missing glyphs
+    pub fn unsupported(&self, text: &str) -> Vec<char> {
+        match self {
+            FontMetrics::TrueType(_) => Vec::new(),
+            FontMetrics::Builtin(_) => text.chars().filter(|&c| !afm::is_encodable(c)).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct Margins {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+impl Default for Margins {
+    fn default() -> Self {
+        Self {
+            top: consts::MARGIN_TOP,
+            right: consts::MARGIN_X,
+            bottom: consts::MARGIN_BOTTOM,
+            left: consts::MARGIN_X,
+        }
+    }
+}
+
+impl Margins {
+    // Usable width between the left and right margins
+    pub fn content_width(&self, page_width: f64) -> f64 {
+        page_width - self.left - self.right
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct TextStyle {
+    pub size: f64,
+    pub char_spacing: f64,
+    pub line_height: f64,
+}
+
+impl TextStyle {
+    pub fn line_height_mm(&self) -> f64 {
+        Mm::from(Pt(self.line_height)).0
+    }
+}
+
+// Lines of text already broken to fit a given width
+#[derive(Debug, Default)]
+pub struct Paragraph {
+    pub lines: Vec<String>,
+    pub line_height: f64,
+}
+
+impl Paragraph {
+    // Vertical space in mm consumed by the wrapped lines
+    pub fn height(&self) -> f64 {
+        self.lines.len() as f64 * self.line_height
+    }
+}
+
+pub struct Layout;
+
+impl Layout {
+    /// Break `text` into lines no wider than `max_width` mm.
+    /// Explicit newlines are kept, and words wider than a full line are split by character.
+    pub fn wrap(metrics: &FontMetrics, text: &str, style: TextStyle, max_width: f64) -> Paragraph {
+        let measure = |s: &str| metrics.text_width(s, style.size, style.char_spacing);
+
+        Paragraph {
+            lines: Self::wrap_with(text, max_width, measure),
+            line_height: style.line_height_mm(),
+        }
+    }
+
+    // Fixed-width wrap for plain text output, counting characters instead of measuring glyphs
+    pub fn wrap_columns(text: &str, columns: usize) -> Vec<String> {
+        Self::wrap_with(text, columns as f64, |s| s.chars().count() as f64)
+    }
+
+    fn wrap_with(text: &str, max_width: f64, measure: impl Fn(&str) -> f64) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for source_line in text.lines() {
+            let mut current = String::new();
+
+            for word in source_line.split_whitespace() {
+                let candidate = if current.is_empty() {
+                    word.to_string()
+                } else {
+                    format!("{} {}", current, word)
+                };
+
+                if measure(&candidate) <= max_width {
+                    current = candidate;
+                    continue;
+                }
+
+                if !current.is_empty() {
+                    lines.push(std::mem::take(&mut current));
+                }
+
+                if measure(word) <= max_width {
+                    current = word.to_string();
+                } else {
+                    for c in word.chars() {
+                        current.push(c);
+                        if measure(&current) > max_width && current.chars().count() > 1 {
+                            current.pop();
+                            lines.push(std::mem::replace(&mut current, c.to_string()));
+                        }
+                    }
+                }
+            }
+
+            lines.push(current);
+        }
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrap(text: &str, columns: usize) -> Vec<String> {
+        Layout::wrap_columns(text, columns)
+    }
+
+    #[test]
+    fn wraps_at_word_boundaries() {
+        assert_eq!(
+            wrap("the quick brown fox jumps", 10),
+            ["the quick", "brown fox", "jumps"]
+        );
+    }
+
+    #[test]
+    fn splits_words_longer_than_the_line() {
+        assert_eq!(
+            wrap("see abcdefghijklmnop end", 6),
+            ["see", "abcdef", "ghijkl", "mnop", "end"]
+        );
+    }
+
+    #[test]
+    fn keeps_explicit_newlines() {
+        assert_eq!(wrap("first line\nsecond", 40), ["first line", "second"]);
+        assert_eq!(wrap("a b c\nd", 3), ["a b", "c", "d"]);
+    }
+
+    #[test]
+    fn keeps_empty_lines() {
+        assert_eq!(wrap("one\n\ntwo", 40), ["one", "", "two"]);
+        assert_eq!(wrap("   ", 40), [""]);
+        assert!(wrap("", 40).is_empty());
+    }
+
+    #[test]
+    fn ignores_trailing_and_repeated_spaces() {
+        assert_eq!(wrap("fits exactly ", 12), ["fits exactly"]);
+        assert_eq!(wrap("a   b  ", 40), ["a b"]);
+    }
+
+    #[test]
+    fn wraps_by_measured_width() {
+        // Courier glyphs are all 600 units wide: 6 pt per character at 10 pt
+        let metrics = FontMetrics::Builtin(BuiltinFont::Courier);
+        let style = TextStyle {
+            size: 10.0,
+            char_spacing: 0.0,
+            line_height: 12.0,
+        };
+        let width = Mm::from(Pt(6.0 * 9.0)).0;
+
+        let paragraph = Layout::wrap(&metrics, "wrap this text please", style, width);
+        assert_eq!(paragraph.lines, ["wrap this", "text", "please"]);
+        assert!((paragraph.height() - 3.0 * style.line_height_mm()).abs() < 1e-9);
+    }
+}
@@ -15,3 +15,16 @@ impl From<DateTime<Utc>> for FormattedDate {
         Self(from_utc_now)
     }
 }
+
+impl std::fmt::Display for FormattedDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FormattedDate {
+    // ISO 8601 timestamp as used in XMP metadata, e.g. 2024-01-31T09:30:00+00:00
+    pub fn iso8601(value: DateTime<Utc>) -> Self {
+        Self(value.to_rfc3339_opts(SecondsFormat::Secs, false))
+    }
+}
@@ -0,0 +1,111 @@
+use printpdf::lopdf::{self, dictionary, Object, StringFormat};
+
+use super::validation;
+
+// A clickable area on one page, in PDF points from the bottom-left corner
+#[derive(Debug, Clone)]
+pub struct Link {
+    pub page: usize,
+    pub rect: [f64; 4],
+    pub uri: String,
+}
+
+// Visible text paired with the URI it should open
+pub type LinkTarget = (String, String);
+
+pub fn mailto(email: &str) -> String {
+    format!("mailto:{}", email.trim())
+}
+
+// Dialers ignore formatting, so keep only the digits and a leading +
+pub fn tel(phone: &str) -> String {
+    let number: String = phone
+        .trim()
+        .chars()
+        .enumerate()
+        .filter(|&(i, c)| c.is_ascii_digit() || (i == 0 && c == '+'))
+        .map(|(_, c)| c)
+        .collect();
+
+    format!("tel:{}", number)
+}
+
+/*
+   Bare domains like "example.com" are opened over https.
+   Anything that is not an http or https URL gets no link and stays plain text.
+*/
+pub fn web(url: &str) -> Option<String> {
+    validation::website(url).ok().filter(|url| !url.is_empty())
+}
+
+/// Split `line` into runs of plain text and runs that are the visible text of a link
+pub fn spans<'a>(line: &'a str, targets: &'a [LinkTarget]) -> Vec<(&'a str, Option<&'a str>)> {
+    let mut spans = Vec::new();
+    let mut rest = line;
+
+    loop {
+        let next = targets
+            .iter()
+            .filter(|(text, _)| !text.is_empty())
+            .filter_map(|(text, uri)| rest.find(text.as_str()).map(|at| (at, text, uri)))
+            .min_by_key(|(at, _, _)| *at);
+
+        let Some((at, text, uri)) = next else {
+            break;
+        };
+
+        if at > 0 {
+            spans.push((&rest[..at], None));
+        }
+
+        spans.push((&rest[at..at + text.len()], Some(uri.as_str())));
+        rest = &rest[at + text.len()..];
+    }
+
+    if !rest.is_empty() || spans.is_empty() {
+        spans.push((rest, None));
+    }
+
+    spans
+}
+
+/*
+   printpdf has no annotation API, so the saved PDF is reopened with lopdf
+   and a URI link annotation attached to each page for every recorded link
+*/
+pub fn annotate(doc: &mut lopdf::Document, links: &[Link]) -> Result<(), anyhow::Error> {
+    let pages = doc.get_pages();
+
+    for (number, page_id) in pages {
+        let annotations: Vec<Object> = links
+            .iter()
+            .filter(|link| link.page + 1 == number as usize)
+            .map(|link| {
+                let annotation = dictionary! {
+                    "Type" => "Annot",
+                    "Subtype" => "Link",
+                    "Rect" => link.rect.iter().map(|&v| Object::Real(v)).collect::<Vec<_>>(),
+                    "Border" => vec![Object::Integer(0); 3],
+                    // Printable, as PDF/A requires
+                    "F" => 4,
+                    "A" => dictionary! {
+                        "S" => "URI",
+                        "URI" => Object::String(link.uri.clone().into_bytes(), StringFormat::Literal),
+                    },
+                };
+
+                Object::Reference(doc.add_object(annotation))
+            })
+            .collect();
+
+        if annotations.is_empty() {
+            continue;
+        }
+
+        doc.get_object_mut(page_id)?
+            .as_dict_mut()?
+            .set("Annots", annotations);
+    }
+
+    Ok(())
+}
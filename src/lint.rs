@@ -0,0 +1,719 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::api::{DocumentShape, Labeled};
+use super::dates::{DateRange, ResumeDate};
+use super::entries::Entries;
+use super::errors::PortformError;
+use super::generate::ResumeWriter;
+use super::theme::Theme;
+
+// Word counts outside of which a description reads as too thin or too dense
+const SUMMARY_WORDS: (usize, usize) = (15, 100);
+const DESCRIPTION_WORDS: (usize, usize) = (10, 150);
+
+// Openers that describe a role instead of an achievement
+const WEAK_OPENERS: [&str; 14] = [
+    "responsible",
+    "helped",
+    "assisted",
+    "worked",
+    "participated",
+    "involved",
+    "tasked",
+    "handled",
+    "duties",
+    "did",
+    "made",
+    "used",
+    "utilized",
+    "tried",
+];
+const PASSIVE_OPENERS: [&str; 6] = ["was", "were", "is", "are", "been", "got"];
+
+// Markers that turn a description line into a bullet point
+const BULLETS: [char; 4] = ['-', '*', '•', '–'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+    MissingSection,
+    MissingDates,
+    DescriptionLength,
+    WeakOpener,
+    DuplicateSkill,
+    DateFormat,
+    DateOrder,
+    PageOverflow,
+    // A configured font could not be loaded, so the page count is unknown
+    FontLoad,
+}
+
+impl Rule {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MissingSection => "missing-section",
+            Self::MissingDates => "missing-dates",
+            Self::DescriptionLength => "description-length",
+            Self::WeakOpener => "weak-opener",
+            Self::DuplicateSkill => "duplicate-skill",
+            Self::DateFormat => "date-format",
+            Self::DateOrder => "date-order",
+            Self::PageOverflow => "page-overflow",
+            Self::FontLoad => "font-load",
+        }
+    }
+}
+
+// A single finding, pointing at the section and entry index used by `edit` and `remove`
+#[derive(Debug, Serialize)]
+pub struct Warning {
+    pub rule: Rule,
+    pub section: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    pub message: String,
+}
+
+impl Warning {
+    // e.g. "employment[2]", or just the section name
+    pub fn location(&self) -> String {
+        match self.index {
+            Some(index) => format!("{}[{}]", self.section, index),
+            None => self.section.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    // Pages the PDF would take with the configured theme, when it could be laid out
+    pub pages: Option<usize>,
+    pub warnings: Vec<Warning>,
+}
+
+impl Report {
+    // Under --strict any warning fails the run, so CI can gate on it
+    pub fn check(&self, strict: bool) -> Result<(), PortformError> {
+        match self.warnings.len() {
+            warnings if strict && warnings > 0 => Err(PortformError::LintFailed { warnings }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/*
+   Resume quality checks. Nothing here stops `write`;
+   the findings are advice, left to the author or a CI gate to act on.
+*/
+pub struct Lint {
+    warnings: Vec<Warning>,
+}
+
+impl Lint {
+    pub fn run(mut document: DocumentShape, theme: Theme, max_pages: usize) -> Report {
+        let mut lint = Self {
+            warnings: Vec::new(),
+        };
+
+        lint.missing_sections(&document);
+        lint.employment_dates(&document);
+        lint.descriptions(&document);
+        lint.duplicate_skills(&document);
+        lint.date_formats(&document);
+        lint.date_order(&document);
+
+        // Sorting changes indices, so it waits until every entry has been reported
+        Entries::sort_dated_sections(&mut document);
+        let pages = lint.page_count(&document, theme, max_pages);
+
+        Report {
+            pages,
+            warnings: lint.warnings,
+        }
+    }
+
+    fn warn(&mut self, rule: Rule, section: &'static str, index: Option<usize>, message: String) {
+        self.warnings.push(Warning {
+            rule,
+            section,
+            index,
+            message,
+        });
+    }
+
+    fn missing_sections(&mut self, document: &DocumentShape) {
+        let blank = |text: Option<&String>| text.is_none_or(|t| t.trim().is_empty());
+        let empty = |len: Option<usize>| len.is_none_or(|len| len == 0);
+
+        let missing = [
+            (
+                "header",
+                blank(document.header.as_ref().map(|h| &h.name)),
+                "no name in the header",
+            ),
+            (
+                "summary",
+                blank(document.summary.as_ref().map(|s| &s.body)),
+                "no summary",
+            ),
+            (
+                "contact",
+                blank(document.contact_details.as_ref().map(|c| &c.email)),
+                "no contact email",
+            ),
+            (
+                "employment",
+                empty(document.employment_history.as_ref().map(Vec::len)),
+                "no employment history",
+            ),
+            (
+                "skills",
+                empty(document.skillset.as_ref().map(Vec::len)),
+                "no skills listed",
+            ),
+        ];
+
+        for (section, is_missing, message) in missing {
+            if is_missing {
+                self.warn(Rule::MissingSection, section, None, message.to_string());
+            }
+        }
+    }
+
+    fn employment_dates(&mut self, document: &DocumentShape) {
+        let history = document.employment_history.as_deref().unwrap_or_default();
+
+        for (index, entry) in history.iter().enumerate() {
+            let message = match &entry.dates_employed {
+                DateRange {
+                    start: None,
+                    end: None,
+                } => "has no dates",
+                DateRange { start: None, .. } => "has no start date",
+                DateRange { end: None, .. } => "has no end date; use `present` for a current role",
+                _ => continue,
+            };
+
+            self.warn(
+                Rule::MissingDates,
+                "employment",
+                Some(index),
+                format!("{:?} {}", entry.label(), message),
+            );
+        }
+    }
+
+    fn descriptions(&mut self, document: &DocumentShape) {
+        if let Some(summary) = &document.summary {
+            self.length("summary", None, &summary.body, SUMMARY_WORDS, true);
+        }
+
+        let employment = document.employment_history.as_deref().unwrap_or_default();
+        for (index, entry) in employment.iter().enumerate() {
+            self.length(
+                "employment",
+                Some(index),
+                &entry.description,
+                DESCRIPTION_WORDS,
+                true,
+            );
+            self.openers("employment", index, &entry.description);
+        }
+
+        let projects = document.projects.as_deref().unwrap_or_default();
+        for (index, project) in projects.iter().enumerate() {
+            self.length(
+                "projects",
+                Some(index),
+                &project.description,
+                DESCRIPTION_WORDS,
+                true,
+            );
+            self.openers("projects", index, &project.description);
+        }
+
+        // Schools often need no description at all, so only a long one is flagged
+        let education = document.education.as_deref().unwrap_or_default();
+        for (index, entry) in education.iter().enumerate() {
+            self.length(
+                "education",
+                Some(index),
+                &entry.description,
+                DESCRIPTION_WORDS,
+                false,
+            );
+        }
+    }
+
+    fn length(
+        &mut self,
+        section: &'static str,
+        index: Option<usize>,
+        text: &str,
+        (min, max): (usize, usize),
+        required: bool,
+    ) {
+        let words = text
+            .split_whitespace()
+            .filter(|word| !word.chars().all(|c| BULLETS.contains(&c)))
+            .count();
+
+        let message = if words == 0 && required {
+            "is empty".to_string()
+        } else if required && words < min {
+            let unit = if words == 1 { "word" } else { "words" };
+            format!("is {} {}; aim for at least {}", words, unit, min)
+        } else if words > max {
+            format!(
+                "is {} words; consider trimming it to {} or fewer",
+                words, max
+            )
+        } else {
+            return;
+        };
+
+        let subject = match index {
+            Some(_) => "Description",
+            None => "Summary",
+        };
+
+        self.warn(
+            Rule::DescriptionLength,
+            section,
+            index,
+            format!("{} {}", subject, message),
+        );
+    }
+
+    // Each line of a description is read as one bullet point
+    fn openers(&mut self, section: &'static str, index: usize, description: &str) {
+        for line in description.lines() {
+            let line = line.trim_start().trim_start_matches(BULLETS).trim();
+            let Some(first) = line.split_whitespace().next() else {
+                continue;
+            };
+            let word = first
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase();
+
+            let problem = if WEAK_OPENERS.contains(&word.as_str()) {
+                "opens with a weak verb; lead with what you achieved"
+            } else if PASSIVE_OPENERS.contains(&word.as_str()) {
+                "opens in the passive voice; lead with the action you took"
+            } else {
+                continue;
+            };
+
+            self.warn(
+                Rule::WeakOpener,
+                section,
+                Some(index),
+                format!("{:?} {}", excerpt(line), problem),
+            );
+        }
+    }
+
+    fn duplicate_skills(&mut self, document: &DocumentShape) {
+        let skills = document.skillset.as_deref().unwrap_or_default();
+        let mut seen: HashMap<String, usize> = HashMap::new();
+
+        for (index, skill) in skills.iter().enumerate() {
+            let key = skill.name.trim().to_lowercase();
+            if key.is_empty() {
+                continue;
+            }
+
+            match seen.get(&key) {
+                Some(first) => self.warn(
+                    Rule::DuplicateSkill,
+                    "skills",
+                    Some(index),
+                    format!("{:?} duplicates skills[{}]", skill.name, first),
+                ),
+                None => {
+                    seen.insert(key, index);
+                }
+            }
+        }
+    }
+
+    // Unrecognized dates, and dates less or more precise than most of the resume
+    fn date_formats(&mut self, document: &DocumentShape) {
+        let mut dates = Vec::new();
+
+        for (index, entry) in document.employment_history.iter().flatten().enumerate() {
+            dates.extend(range_dates("employment", index, &entry.dates_employed));
+        }
+        for (index, entry) in document.education.iter().flatten().enumerate() {
+            dates.extend(range_dates("education", index, &entry.dates));
+        }
+        for (index, cert) in document.certifications.iter().flatten().enumerate() {
+            if let Some(date) = &cert.date_issued {
+                dates.push(("certs", index, "issue", date));
+            }
+        }
+
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for (_, _, _, date) in &dates {
+            if let Some(precision) = precision(date) {
+                *counts.entry(precision).or_default() += 1;
+            }
+        }
+        // Ties go to month and year, the most common form on a resume
+        let usual = counts
+            .iter()
+            .max_by_key(|(precision, count)| (**count, **precision == "month and year"))
+            .map(|(precision, _)| *precision);
+
+        for (section, index, bound, date) in dates {
+            let message = match (date, precision(date)) {
+                (ResumeDate::Text(text), _) => format!(
+                    "{} date {:?} is not a recognized date; use a form like 2021-03 or Mar 2021",
+                    bound, text
+                ),
+                (_, Some(precision)) if usual.is_some_and(|usual| usual != precision) => format!(
+                    "{} date {} gives the {} while most dates give the {}",
+                    bound,
+                    date,
+                    precision,
+                    usual.unwrap_or_default()
+                ),
+                _ => continue,
+            };
+
+            self.warn(Rule::DateFormat, section, Some(index), message);
+        }
+    }
+
+    fn date_order(&mut self, document: &DocumentShape) {
+        for (section, index, _, e) in document.invalid_date_ranges() {
+            self.warn(Rule::DateOrder, section, Some(index), e.to_string());
+        }
+    }
+
+    // Lays the resume out exactly as `write` would, without saving it
+    fn page_count(
+        &mut self,
+        document: &DocumentShape,
+        theme: Theme,
+        max_pages: usize,
+    ) -> Option<usize> {
+        let title = document.title.clone().unwrap_or_default();
+        let writer =
+            ResumeWriter::new(title, theme).load_fonts(&document.fonts.clone().unwrap_or_default());
+
+        let mut writer = match writer {
+            Ok(writer) => writer,
+            Err(e) => {
+                self.warn(
+                    Rule::FontLoad,
+                    "fonts",
+                    None,
+                    format!("{:#}. The page count could not be estimated", e),
+                );
+                return None;
+            }
+        };
+
+        if let Some(header) = document.header.clone() {
+            writer.header_section(header.name, header.profession);
+        }
+        writer.body_sections(document);
+
+        let pages = writer.page_count();
+        if pages > max_pages {
+            self.warn(
+                Rule::PageOverflow,
+                "resume",
+                None,
+                format!(
+                    "Runs to {} pages, over the limit of {}; tighten descriptions or drop older entries",
+                    pages, max_pages
+                ),
+            );
+        }
+
+        Some(pages)
+    }
+}
+
+type DatedField<'a> = (&'static str, usize, &'static str, &'a ResumeDate);
+
+fn range_dates<'a>(
+    section: &'static str,
+    index: usize,
+    range: &'a DateRange,
+) -> Vec<DatedField<'a>> {
+    [("start", &range.start), ("end", &range.end)]
+        .into_iter()
+        .filter_map(|(bound, date)| date.as_ref().map(|date| (section, index, bound, date)))
+        .collect()
+}
+
+// Present is not written by the author with any precision, and text has none
+fn precision(date: &ResumeDate) -> Option<&'static str> {
+    match date {
+        ResumeDate::Year(_) => Some("year only"),
+        ResumeDate::Month(..) => Some("month and year"),
+        ResumeDate::Day(_) => Some("full date"),
+        ResumeDate::Present | ResumeDate::Text(_) => None,
+    }
+}
+
+// First few words of a bullet, enough to find it again
+fn excerpt(line: &str) -> String {
+    const LIMIT: usize = 40;
+
+    match line.char_indices().nth(LIMIT) {
+        Some((end, _)) => format!("{}…", line[..end].trim_end()),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{
+        Certification, ContactDetails, FontFamily, Header, HistoryEntry, Skill, Summary,
+    };
+
+    const DESCRIPTION: &str =
+        "Led the migration of twelve services to a shared deployment pipeline";
+
+    fn date(text: &str) -> Option<ResumeDate> {
+        Some(ResumeDate::parse(text).unwrap())
+    }
+
+    fn job(position: &str, start: &str, end: &str, description: &str) -> HistoryEntry {
+        HistoryEntry {
+            position: position.to_string(),
+            location: "Remote".to_string(),
+            dates_employed: DateRange {
+                start: Some(start).filter(|s| !s.is_empty()).and_then(date),
+                end: Some(end).filter(|e| !e.is_empty()).and_then(date),
+            },
+            description: description.to_string(),
+            pinned: false,
+        }
+    }
+
+    // A resume that passes every rule, for each test to break in one place
+    fn complete() -> DocumentShape {
+        DocumentShape {
+            header: Some(Header {
+                name: "Jane Doe".to_string(),
+                profession: "Engineer".to_string(),
+            }),
+            summary: Some(Summary {
+                body: "Engineer with ten years of experience building reliable backend systems for small and growing product teams"
+                    .to_string(),
+            }),
+            contact_details: Some(ContactDetails {
+                email: "jane@example.com".to_string(),
+                ..ContactDetails::default()
+            }),
+            employment_history: Some(vec![job("Engineer", "2021-03", "present", DESCRIPTION)]),
+            skillset: Some(vec![Skill {
+                name: "Rust".to_string(),
+            }]),
+            ..DocumentShape::default()
+        }
+    }
+
+    fn rules(document: DocumentShape) -> Vec<(Rule, String)> {
+        Lint::run(document, Theme::default(), 2)
+            .warnings
+            .into_iter()
+            .map(|warning| (warning.rule, warning.location()))
+            .collect()
+    }
+
+    #[test]
+    fn passes_a_complete_resume() {
+        assert_eq!(rules(complete()), []);
+    }
+
+    #[test]
+    fn reports_missing_sections() {
+        let document = DocumentShape {
+            summary: None,
+            skillset: Some(Vec::new()),
+            ..complete()
+        };
+
+        assert_eq!(
+            rules(document),
+            [
+                (Rule::MissingSection, "summary".to_string()),
+                (Rule::MissingSection, "skills".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_missing_employment_dates() {
+        let mut document = complete();
+        document.employment_history = Some(vec![
+            job("Engineer", "2021-03", "", DESCRIPTION),
+            job("Intern", "", "", DESCRIPTION),
+        ]);
+
+        assert_eq!(
+            rules(document),
+            [
+                (Rule::MissingDates, "employment[0]".to_string()),
+                (Rule::MissingDates, "employment[1]".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_descriptions_that_are_too_short_or_too_long() {
+        let mut document = complete();
+        document.summary = Some(Summary {
+            body: "Engineer".to_string(),
+        });
+        document.employment_history = Some(vec![
+            job("Engineer", "2021-03", "present", &"word ".repeat(151)),
+            job("Intern", "2019-01", "2020-01", "- "),
+        ]);
+
+        assert_eq!(
+            rules(document),
+            [
+                (Rule::DescriptionLength, "summary".to_string()),
+                (Rule::DescriptionLength, "employment[0]".to_string()),
+                (Rule::DescriptionLength, "employment[1]".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_weak_and_passive_openers_per_line() {
+        let description = format!(
+            "{}\n- Responsible for the build system\n• Was asked to lead on-call",
+            DESCRIPTION
+        );
+        let mut document = complete();
+        document.employment_history =
+            Some(vec![job("Engineer", "2021-03", "present", &description)]);
+
+        let report = Lint::run(document, Theme::default(), 2);
+        let messages: Vec<&str> = report
+            .warnings
+            .iter()
+            .filter(|w| w.rule == Rule::WeakOpener)
+            .map(|w| w.message.as_str())
+            .collect();
+
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].contains("weak verb"));
+        assert!(messages[1].contains("passive voice"));
+    }
+
+    #[test]
+    fn reports_duplicate_skills_ignoring_case() {
+        let mut document = complete();
+        document.skillset = Some(
+            ["Rust", "Go", " rust "]
+                .map(|name| Skill {
+                    name: name.to_string(),
+                })
+                .to_vec(),
+        );
+
+        let report = Lint::run(document, Theme::default(), 2);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].rule, Rule::DuplicateSkill);
+        assert_eq!(report.warnings[0].location(), "skills[2]");
+        assert!(report.warnings[0].message.contains("skills[0]"));
+    }
+
+    #[test]
+    fn reports_dates_unlike_the_majority_and_unrecognized_text() {
+        let mut document = complete();
+        document.employment_history = Some(vec![
+            job("Engineer", "2021-03", "present", DESCRIPTION),
+            job("Developer", "2019-01", "2021-02", DESCRIPTION),
+            job("Intern", "2018", "2018-12", DESCRIPTION),
+        ]);
+        document.certifications = Some(vec![Certification {
+            date_issued: Some(ResumeDate::Text("Spring '20".to_string())),
+            name: "Cloud".to_string(),
+            pinned: false,
+        }]);
+
+        assert_eq!(
+            rules(document),
+            [
+                (Rule::DateFormat, "employment[2]".to_string()),
+                (Rule::DateFormat, "certs[0]".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_reversed_date_ranges() {
+        let mut document = complete();
+        document.employment_history =
+            Some(vec![job("Engineer", "2021-03", "2020-01", DESCRIPTION)]);
+
+        assert_eq!(
+            rules(document),
+            [(Rule::DateOrder, "employment[0]".to_string())]
+        );
+    }
+
+    #[test]
+    fn reports_page_overflow() {
+        let mut document = complete();
+        document.employment_history = Some(
+            (0..40)
+                .map(|i| job(&format!("Role {}", i), "2001-01", "2002-01", DESCRIPTION))
+                .collect(),
+        );
+
+        let report = Lint::run(document, Theme::default(), 1);
+        assert!(report.pages.unwrap() > 1);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].rule, Rule::PageOverflow);
+    }
+
+    #[test]
+    fn reports_fonts_that_fail_to_load() {
+        let mut document = complete();
+        document.fonts = Some(FontFamily {
+            regular: Some("/nonexistent/font.ttf".to_string()),
+            ..FontFamily::default()
+        });
+
+        let report = Lint::run(document, Theme::default(), 2);
+        assert_eq!(report.pages, None);
+        assert_eq!(
+            report
+                .warnings
+                .iter()
+                .map(|w| (w.rule, w.location()))
+                .collect::<Vec<_>>(),
+            [(Rule::FontLoad, "fonts".to_string())]
+        );
+    }
+
+    #[test]
+    fn strict_mode_fails_on_any_warning() {
+        let clean = Lint::run(complete(), Theme::default(), 2);
+        assert!(clean.check(true).is_ok());
+
+        let document = DocumentShape {
+            summary: None,
+            ..complete()
+        };
+        let report = Lint::run(document, Theme::default(), 2);
+        assert!(report.check(false).is_ok());
+
+        let error = anyhow::Error::from(report.check(true).unwrap_err());
+        assert_eq!(PortformError::exit_code_of(&error), 9);
+    }
+}
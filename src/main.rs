@@ -1,20 +1,41 @@
+mod afm;
 mod api;
 mod cli;
 mod consts;
+mod dates;
+mod editor;
+mod entries;
 mod errors;
 mod file_io;
+mod fonts;
 mod generate;
+mod html;
+mod jsonresume;
+mod layout;
+mod links;
+mod lint;
+mod metadata;
 mod os;
+mod profiles;
+mod sections;
+mod text;
+mod theme;
+mod validation;
+mod wizard;
 
-use cli::Handler;
-use errors::Error;
-
-fn main() -> Result<(), Error> {
-    //
+use std::process::ExitCode;
 
-    cli::CLParser::handle_input()?;
+use cli::Handler;
+use errors::PortformError;
 
+fn main() -> ExitCode {
     //
 
-    Ok(())
+    match cli::CLParser::handle_input() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::from(PortformError::exit_code_of(&e))
+        }
+    }
 }
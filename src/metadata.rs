@@ -0,0 +1,375 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use pf_lib::FormattedDate;
+use printpdf::lopdf::{self, dictionary, Object, Stream, StringFormat};
+use printpdf::{OffsetDateTime, PdfDocumentReference};
+
+use super::api::DocumentShape;
+use super::html::escape;
+
+const CREATOR: &str = "portform";
+const SRGB_IDENTIFIER: &str = "sRGB IEC61966-2.1";
+// Bytes above 127 in the comment after the header, as PDF/A requires
+const BINARY_MARKER: [u8; 4] = [0xe2, 0xe3, 0xcf, 0xd3];
+// As long as the marker, so swapping it in moves no xref offset
+const MARKER_PLACEHOLDER: &str = "????";
+
+// Document properties read by PDF viewers and applicant tracking systems
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    pub title: String,
+    pub author: String,
+    pub subject: String,
+    pub keywords: Vec<String>,
+    pub created: DateTime<Utc>,
+    // Emit PDF/A-2b: XMP identification plus an sRGB output intent
+    pub pdfa: bool,
+}
+
+impl Metadata {
+    // The title is resolved by the caller, which knows the fallbacks for an untitled config
+    pub fn from_document(document: &DocumentShape, title: &str, pdfa: bool) -> Self {
+        let header = document.header.clone().unwrap_or_default();
+
+        let subject = match header.profession.trim() {
+            "" => "Resume".to_string(),
+            profession => format!("Resume - {}", profession),
+        };
+
+        let keywords = document
+            .skillset
+            .iter()
+            .flatten()
+            .map(|skill| skill.name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        Self {
+            title: title.to_string(),
+            author: header.name,
+            subject,
+            keywords,
+            created: Utc::now(),
+            pdfa,
+        }
+    }
+
+    fn producer() -> String {
+        format!("{} {} (printpdf)", CREATOR, env!("CARGO_PKG_VERSION"))
+    }
+
+    // Fill the document information dictionary through printpdf
+    pub fn apply(&self, doc: PdfDocumentReference) -> PdfDocumentReference {
+        let date = OffsetDateTime::from_unix_timestamp(self.created.timestamp());
+
+        doc.with_author(self.author.clone())
+            .with_subject(self.subject.clone())
+            .with_keywords(self.keywords.clone())
+            .with_creator(CREATOR)
+            .with_producer(Self::producer())
+            .with_creation_date(date)
+            .with_mod_date(date)
+            .with_metadata_date(date)
+    }
+
+    /*
+       printpdf only writes XMP for PDF/X and stores info strings as raw UTF-8,
+       so finish the saved document here: re-encode the info dictionary,
+       attach a matching XMP packet and, for PDF/A, an sRGB output intent
+    */
+    pub fn embed(&self, doc: &mut lopdf::Document) -> Result<(), anyhow::Error> {
+        let info_id = doc
+            .trailer
+            .get(b"Info")
+            .and_then(Object::as_reference)
+            .context("Generated PDF has no document information dictionary")?;
+        let info = doc.get_object_mut(info_id)?.as_dict_mut()?;
+
+        // printpdf leaves empty PDF/X entries behind even when PDF/X is not requested
+        info.remove(b"GTS_PDFXVersion");
+        info.remove(b"Identifier");
+
+        for (key, value) in [
+            ("Title", self.title.clone()),
+            ("Author", self.author.clone()),
+            ("Subject", self.subject.clone()),
+            ("Keywords", self.keywords.join(",")),
+        ] {
+            info.set(key, text_string(&value));
+        }
+
+        let xmp = Stream::new(
+            dictionary! { "Type" => "Metadata", "Subtype" => "XML" },
+            self.xmp().into_bytes(),
+        );
+        let xmp_id = doc.add_object(xmp);
+
+        let output_intent = if self.pdfa {
+            let profile = Stream::new(dictionary! { "N" => 3 }, srgb_profile());
+            let profile_id = doc.add_object(profile);
+
+            Some(dictionary! {
+                "Type" => "OutputIntent",
+                "S" => "GTS_PDFA1",
+                "OutputConditionIdentifier" => literal(SRGB_IDENTIFIER),
+                "Info" => literal(SRGB_IDENTIFIER),
+                "RegistryName" => literal("http://www.color.org"),
+                "DestOutputProfile" => Object::Reference(profile_id),
+            })
+        } else {
+            None
+        };
+
+        let catalog_id = doc.trailer.get(b"Root").and_then(Object::as_reference)?;
+        let catalog = doc.get_object_mut(catalog_id)?.as_dict_mut()?;
+        catalog.set("Metadata", Object::Reference(xmp_id));
+
+        if let Some(intent) = output_intent {
+            catalog.set("OutputIntents", vec![Object::Dictionary(intent)]);
+        }
+
+        if self.pdfa {
+            Self::name_layer_config(doc, catalog_id)?;
+
+            /*
+               PDF/A wants a comment of non-ASCII bytes right after the header so the file
+               is treated as binary. lopdf writes only "%PDF-<version>" from a UTF-8 string,
+               so a placeholder goes there and `serialize` swaps in the raw bytes.
+            */
+            doc.version = format!("1.7\n%{}", MARKER_PLACEHOLDER);
+        }
+
+        Ok(())
+    }
+
+    /// Save `doc` to bytes, writing the binary marker comment `embed` left a placeholder for
+    pub fn serialize(doc: &mut lopdf::Document) -> Result<Vec<u8>, std::io::Error> {
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes)?;
+
+        let header = format!("%PDF-1.7\n%{}\n", MARKER_PLACEHOLDER);
+        if bytes.starts_with(header.as_bytes()) {
+            let end = header.len() - 1;
+            bytes[end - BINARY_MARKER.len()..end].copy_from_slice(&BINARY_MARKER);
+        }
+
+        Ok(bytes)
+    }
+
+    // PDF/A-2 requires a Name in the default optional content configuration printpdf writes
+    fn name_layer_config(
+        doc: &mut lopdf::Document,
+        catalog_id: lopdf::ObjectId,
+    ) -> Result<(), anyhow::Error> {
+        let catalog = doc.get_object(catalog_id)?.as_dict()?;
+        let properties = match catalog.get(b"OCProperties") {
+            Ok(Object::Reference(id)) => *id,
+            Ok(_) => catalog_id,
+            Err(_) => return Ok(()),
+        };
+
+        let dict = doc.get_object_mut(properties)?.as_dict_mut()?;
+        let dict = match dict.get_mut(b"OCProperties") {
+            Ok(inline) => inline.as_dict_mut()?,
+            Err(_) => dict,
+        };
+
+        if let Ok(config) = dict.get_mut(b"D").and_then(Object::as_dict_mut) {
+            config.set("Name", literal(CREATOR));
+        }
+
+        Ok(())
+    }
+
+    fn xmp(&self) -> String {
+        let date = FormattedDate::iso8601(self.created).to_string();
+        let keywords: String = self
+            .keywords
+            .iter()
+            .map(|keyword| format!("<rdf:li>{}</rdf:li>", escape(keyword)))
+            .collect();
+
+        let (pdfaid_ns, pdfaid) = if self.pdfa {
+            (
+                "\n    xmlns:pdfaid=\"http://www.aiim.org/pdfa/ns/id/\"",
+                "\n   <pdfaid:part>2</pdfaid:part>\n   <pdfaid:conformance>B</pdfaid:conformance>",
+            )
+        } else {
+            ("", "")
+        };
+
+        format!(
+            r#"<?xpacket begin="{bom}" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+    xmlns:pdf="http://ns.adobe.com/pdf/1.3/"{pdfaid_ns}>
+   <dc:format>application/pdf</dc:format>
+   <dc:title><rdf:Alt><rdf:li xml:lang="x-default">{title}</rdf:li></rdf:Alt></dc:title>
+   <dc:creator><rdf:Seq><rdf:li>{author}</rdf:li></rdf:Seq></dc:creator>
+   <dc:description><rdf:Alt><rdf:li xml:lang="x-default">{subject}</rdf:li></rdf:Alt></dc:description>
+   <dc:subject><rdf:Bag>{keywords}</rdf:Bag></dc:subject>
+   <xmp:CreatorTool>{creator}</xmp:CreatorTool>
+   <xmp:CreateDate>{date}</xmp:CreateDate>
+   <xmp:ModifyDate>{date}</xmp:ModifyDate>
+   <xmp:MetadataDate>{date}</xmp:MetadataDate>
+   <pdf:Producer>{producer}</pdf:Producer>
+   <pdf:Keywords>{keyword_list}</pdf:Keywords>
+   <pdf:Trapped>False</pdf:Trapped>{pdfaid}
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#,
+            bom = '\u{feff}',
+            title = escape(&self.title),
+            author = escape(&self.author),
+            subject = escape(&self.subject),
+            creator = CREATOR,
+            producer = escape(&Self::producer()),
+            keyword_list = escape(&self.keywords.join(",")),
+        )
+    }
+}
+
+fn literal(text: &str) -> Object {
+    Object::String(text.as_bytes().to_vec(), StringFormat::Literal)
+}
+
+// PDF text strings are PDFDocEncoding, so anything beyond ASCII is written as UTF-16BE
+fn text_string(text: &str) -> Object {
+    if text.is_ascii() {
+        return literal(text);
+    }
+
+    let bytes = [0xfe, 0xff]
+        .into_iter()
+        .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+        .collect();
+
+    Object::String(bytes, StringFormat::Hexadecimal)
+}
+
+/*
+   Minimal ICC v2 display profile for sRGB: D50-adapted primaries and a 2.2 gamma curve.
+   PDF/A needs an output intent matching the DeviceRGB colors every theme uses.
+*/
+fn srgb_profile() -> Vec<u8> {
+    fn s15f16(value: f64) -> [u8; 4] {
+        ((value * 65536.0).round() as i32).to_be_bytes()
+    }
+
+    fn xyz(x: f64, y: f64, z: f64) -> Vec<u8> {
+        [
+            b"XYZ ".as_slice(),
+            &[0; 4],
+            &s15f16(x),
+            &s15f16(y),
+            &s15f16(z),
+        ]
+        .concat()
+    }
+
+    let mut desc = [b"desc".as_slice(), &[0; 4]].concat();
+    desc.extend((SRGB_IDENTIFIER.len() as u32 + 1).to_be_bytes());
+    desc.extend(SRGB_IDENTIFIER.as_bytes());
+    desc.extend([0; 1 + 4 + 4 + 2 + 1 + 67]);
+
+    let cprt = [b"text".as_slice(), &[0; 4], b"No copyright, use freely\0"].concat();
+    let curve = [
+        b"curv".as_slice(),
+        &[0; 4],
+        &1u32.to_be_bytes(),
+        &[0x02, 0x33],
+    ]
+    .concat();
+
+    let tags: [(&[u8; 4], Vec<u8>); 9] = [
+        (b"desc", desc),
+        (b"cprt", cprt),
+        (b"wtpt", xyz(0.9642, 1.0, 0.8249)),
+        (b"rXYZ", xyz(0.4361, 0.2225, 0.0139)),
+        (b"gXYZ", xyz(0.3851, 0.7169, 0.0971)),
+        (b"bXYZ", xyz(0.1431, 0.0606, 0.7141)),
+        (b"rTRC", curve.clone()),
+        (b"gTRC", curve.clone()),
+        (b"bTRC", curve),
+    ];
+
+    let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+    let mut data = Vec::new();
+    let data_start = 128 + 4 + tags.len() * 12;
+
+    for (signature, bytes) in &tags {
+        table.extend(signature.as_slice());
+        table.extend(((data_start + data.len()) as u32).to_be_bytes());
+        table.extend((bytes.len() as u32).to_be_bytes());
+
+        data.extend(bytes);
+        // Tag data starts on 4-byte boundaries
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+
+    let size = (data_start + data.len()) as u32;
+    let mut header = Vec::with_capacity(128);
+    header.extend(size.to_be_bytes());
+    header.extend([0; 4]); // preferred CMM
+    header.extend([0x02, 0x10, 0, 0]); // version 2.1
+    header.extend(b"mntrRGB XYZ ");
+    header.extend([0; 12]); // creation date
+    header.extend(b"acsp");
+    header.extend([0; 4 + 4 + 4 + 4 + 8 + 4]); // platform, flags, device, attributes, intent
+    header.extend(s15f16(0.9642));
+    header.extend(s15f16(1.0));
+    header.extend(s15f16(0.8249));
+    header.resize(128, 0);
+
+    [header, table, data].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> lopdf::Document {
+        let mut doc = lopdf::Document::with_version("1.7");
+        let info_id = doc.add_object(dictionary! {});
+        let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog" });
+        doc.trailer.set("Info", Object::Reference(info_id));
+        doc.trailer.set("Root", Object::Reference(catalog_id));
+        doc
+    }
+
+    fn metadata(pdfa: bool) -> Metadata {
+        Metadata::from_document(&DocumentShape::default(), "Resume", pdfa)
+    }
+
+    #[test]
+    fn writes_the_binary_marker_after_the_pdfa_header() {
+        let mut doc = document();
+        metadata(true).embed(&mut doc).unwrap();
+        let bytes = Metadata::serialize(&mut doc).unwrap();
+
+        assert_eq!(
+            &bytes[..15],
+            b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n",
+            "{:?}",
+            String::from_utf8_lossy(&bytes[..15])
+        );
+        // The xref offsets still point at the objects
+        let loaded = lopdf::Document::load_mem(&bytes).unwrap();
+        let catalog_id = loaded.trailer.get(b"Root").and_then(Object::as_reference);
+        assert!(loaded.get_object(catalog_id.unwrap()).is_ok());
+    }
+
+    #[test]
+    fn leaves_other_output_without_a_marker() {
+        let mut doc = document();
+        metadata(false).embed(&mut doc).unwrap();
+        let bytes = Metadata::serialize(&mut doc).unwrap();
+
+        assert!(bytes.starts_with(b"%PDF-1.7\n"));
+        assert_ne!(bytes[9], b'%');
+    }
+}
@@ -1,4 +1,11 @@
-use std::path::PathBuf;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::Context;
+
+use super::errors::PortformError;
+use super::profiles::{Profiles, DEFAULT_PROFILE, PROFILES_DIR};
 
 enum UserOS {
     Linux,
@@ -10,39 +17,211 @@ enum UserOS {
 impl UserOS {
     pub fn get() -> Self {
         // exhaustive match patterns
-        match std::env::consts::OS {
+        match env::consts::OS {
             "linux" => UserOS::Linux,
             "macos" => UserOS::MacOS,
             "windows" => UserOS::Windows,
             _ => UserOS::Unsupported, // wildcard
         }
     }
+
+    // Where versions before the XDG move kept the config, if anywhere
+    fn legacy_path(&self) -> Option<PathBuf> {
+        match self {
+            UserOS::Linux | UserOS::MacOS => Some(PathBuf::from(LEGACY_UNIX_PATH)),
+            UserOS::Windows => Some(env::temp_dir().join(CONFIG_FILENAME)),
+            UserOS::Unsupported => None,
+        }
+    }
 }
 
 #[derive(Debug)]
-pub struct TempPath(pub PathBuf);
+pub struct ConfigPath(pub PathBuf);
+
+// Per-user directory holding everything portform stores
+#[derive(Debug)]
+pub struct ConfigDir(pub PathBuf);
+
+const APP_DIR: &str = "portform";
+const CONFIG_FILENAME: &str = "portform_config.json";
+const LEGACY_UNIX_PATH: &str = "/tmp/portform_config.json";
 
-const UNIX_PATH: &str = "/tmp/portform_config.json";
+pub const CONFIG_ENV: &str = "PORTFORM_CONFIG";
+
+// Set once from the global `--config` flag, taking priority over everything else
+static CONFIG_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
 
 // TryFrom trait enables idiomatic conversion between custom types
-impl TryFrom<UserOS> for TempPath {
+impl TryFrom<UserOS> for ConfigDir {
     type Error = anyhow::Error;
 
     fn try_from(value: UserOS) -> Result<Self, anyhow::Error> {
         // exhaust all possible enum variants
-        let destination = match value {
-            UserOS::Linux | UserOS::MacOS => PathBuf::from(UNIX_PATH),
-            UserOS::Windows => std::env::temp_dir().join("portform_config.json"),
-            UserOS::Unsupported => panic!("Your OS is not currently supported ... =("),
+        let config_dir = match value {
+            UserOS::Linux | UserOS::MacOS => match env::var_os("XDG_CONFIG_HOME") {
+                Some(dir) if Path::new(&dir).is_absolute() => PathBuf::from(dir),
+                _ => home_dir()?.join(".config"),
+            },
+            UserOS::Windows => env::var_os("APPDATA")
+                .map(PathBuf::from)
+                .context("APPDATA is not set, unable to locate a config directory")?,
+            UserOS::Unsupported => Err(PortformError::UnsupportedPlatform {
+                os: env::consts::OS.to_string(),
+            })?,
         };
 
-        let temp_path = Self(destination);
+        Ok(Self(config_dir.join(APP_DIR)))
+    }
+}
+
+fn home_dir() -> Result<PathBuf, anyhow::Error> {
+    env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .context("HOME is not set, unable to locate a config directory")
+}
+
+pub fn set_config_override(path: PathBuf) {
+    // Parsed once per process, so a second call can only repeat the same value
+    let _ = CONFIG_OVERRIDE.set(path);
+}
 
-        Ok(temp_path)
+/*
+   Resolve the config file in priority order:
+   --config flag, PORTFORM_CONFIG, then the active profile
+*/
+pub fn resolve_config_path() -> Result<ConfigPath, anyhow::Error> {
+    if let Some(path) = CONFIG_OVERRIDE.get() {
+        return Ok(ConfigPath(path.clone()));
     }
+
+    if let Some(path) = env::var_os(CONFIG_ENV).filter(|path| !path.is_empty()) {
+        return Ok(ConfigPath(PathBuf::from(path)));
+    }
+
+    let active = Profiles::active()?;
+
+    Ok(ConfigPath(Profiles::path(&active)?))
+}
+
+// Resolve the per-user directory, moving a config from the old temp location into the default profile
+pub fn config_dir() -> Result<ConfigDir, anyhow::Error> {
+    let user_os = UserOS::get();
+    let legacy = user_os.legacy_path();
+    let config_dir = ConfigDir::try_from(user_os)?;
+
+    let default_profile = config_dir
+        .0
+        .join(PROFILES_DIR)
+        .join(format!("{}.json", DEFAULT_PROFILE));
+
+    if let Some(legacy) = legacy {
+        migrate_legacy_config(&legacy, &default_profile)?;
+    }
+
+    Ok(config_dir)
 }
 
-pub fn get_os_config_path() -> Result<TempPath, anyhow::Error> {
-    // Attempt conversion, propagate any errors to be handled by caller
-    TempPath::try_from(UserOS::get())
+// One-time move of a config left behind in the old temp location
+fn migrate_legacy_config(legacy: &Path, destination: &Path) -> Result<(), anyhow::Error> {
+    if destination.exists() || !owned_regular_file(legacy) {
+        return Ok(());
+    }
+
+    if let Some(parent) = destination.parent() {
+        create_private_dir(parent)?;
+    }
+
+    // rename fails across filesystems (/tmp is often tmpfs), so fall back to copying
+    if std::fs::rename(legacy, destination).is_err() {
+        std::fs::copy(legacy, destination)
+            .with_context(|| format!("Unable to migrate config from {}", legacy.display()))?;
+
+        // The config already lives at its new location, so a leftover is only worth a note
+        if let Err(e) = std::fs::remove_file(legacy) {
+            eprintln!(
+                "Unable to remove {} after migrating it: {}",
+                legacy.display(),
+                e
+            );
+        }
+    }
+
+    restrict_permissions(destination)?;
+
+    eprintln!(
+        "Moved existing config from {} to {}",
+        legacy.display(),
+        destination.display()
+    );
+
+    Ok(())
+}
+
+/*
+   Legacy configs may sit in a shared directory like /tmp, where anyone can plant a file.
+   Only a regular file (not a symlink) owned by the current user is taken over.
+*/
+fn owned_regular_file(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return false;
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        // SAFETY: getuid has no preconditions and cannot fail
+        let uid = unsafe { libc::getuid() };
+        metadata.file_type().is_file() && metadata.uid() == uid
+    }
+
+    #[cfg(not(unix))]
+    metadata.file_type().is_file()
+}
+
+// Resume data is personal, so directories and files are readable by the owner only
+pub fn create_private_dir(dir: &Path) -> Result<(), anyhow::Error> {
+    if dir.as_os_str().is_empty() || dir.exists() {
+        return Ok(());
+    }
+
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+
+    builder
+        .create(dir)
+        .with_context(|| format!("Unable to create config directory {}", dir.display()))
+}
+
+// Fails if anything, including a symlink, already exists at the path
+pub fn create_private_file(file: &Path) -> std::io::Result<std::fs::File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    options.open(file)
+}
+
+pub fn restrict_permissions(file: &Path) -> Result<(), anyhow::Error> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(file, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    #[cfg(not(unix))]
+    let _ = file;
+
+    Ok(())
 }
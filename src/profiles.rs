@@ -0,0 +1,161 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use anyhow::{bail, Context};
+
+use super::api::DocumentShape;
+use super::file_io::{ConfigFileHandler, ConfigFormat};
+use super::os;
+
+pub const DEFAULT_PROFILE: &str = "default";
+pub const PROFILES_DIR: &str = "profiles";
+const ACTIVE_PROFILE_FILE: &str = "active_profile";
+
+// Set once from the global `--profile` flag, taking priority over the stored active profile
+static PROFILE_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+// Named resume variants stored side by side in the config directory
+pub struct Profiles;
+
+impl Profiles {
+    pub fn set_override(name: String) {
+        let _ = PROFILE_OVERRIDE.set(name);
+    }
+
+    pub fn dir() -> Result<PathBuf, anyhow::Error> {
+        Ok(os::config_dir()?.0.join(PROFILES_DIR))
+    }
+
+    // A profile may be stored in any config format, falling back to JSON for new ones
+    pub fn path(name: &str) -> Result<PathBuf, anyhow::Error> {
+        Self::validate_name(name)?;
+        let dir = Self::dir()?;
+
+        let existing = ConfigFormat::EXTENSIONS
+            .iter()
+            .map(|ext| dir.join(format!("{}.{}", name, ext)))
+            .find(|path| path.is_file());
+
+        Ok(existing.unwrap_or_else(|| dir.join(format!("{}.json", name))))
+    }
+
+    pub fn exists(name: &str) -> Result<bool, anyhow::Error> {
+        Ok(Self::path(name)?.is_file())
+    }
+
+    pub fn active() -> Result<String, anyhow::Error> {
+        if let Some(name) = PROFILE_OVERRIDE.get() {
+            return Ok(name.clone());
+        }
+
+        let marker = os::config_dir()?.0.join(ACTIVE_PROFILE_FILE);
+
+        match std::fs::read_to_string(marker) {
+            Ok(name) if !name.trim().is_empty() => Ok(name.trim().to_string()),
+            _ => Ok(DEFAULT_PROFILE.to_string()),
+        }
+    }
+
+    pub fn set_active(name: &str) -> Result<(), anyhow::Error> {
+        Self::require(name)?;
+
+        let config_dir = os::config_dir()?.0;
+        os::create_private_dir(&config_dir)?;
+        std::fs::write(config_dir.join(ACTIVE_PROFILE_FILE), name)?;
+
+        Ok(())
+    }
+
+    pub fn list() -> Result<Vec<String>, anyhow::Error> {
+        let dir = Self::dir()?;
+
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut names: Vec<String> = std::fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| ConfigFormat::EXTENSIONS.contains(&ext))
+            })
+            .filter_map(|path| path.file_stem()?.to_str().map(String::from))
+            .collect();
+
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    pub fn create(name: &str, extension: &str) -> Result<(), anyhow::Error> {
+        Self::require_absent(name)?;
+
+        let path = Self::path(name)?.with_extension(extension);
+        ConfigFileHandler::write_to(&path, &DocumentShape::default())
+    }
+
+    // The copy is stored in the same format as the original
+    pub fn copy(from: &str, to: &str) -> Result<(), anyhow::Error> {
+        Self::require(from)?;
+        Self::require_absent(to)?;
+
+        let source = Self::path(from)?;
+        let mut destination = Self::path(to)?;
+
+        if let Some(extension) = source.extension() {
+            destination.set_extension(extension);
+        }
+
+        let document_config = ConfigFileHandler::read_from(&source)?;
+        ConfigFileHandler::write_to(&destination, &document_config)
+    }
+
+    pub fn delete(name: &str) -> Result<(), anyhow::Error> {
+        Self::require(name)?;
+
+        if Self::active()? == name {
+            bail!(
+                "Profile {:?} is active. Switch with `portform profile use <name>` before deleting it.",
+                name
+            );
+        }
+
+        let path = Self::path(name)?;
+        std::fs::remove_file(&path).with_context(|| format!("Unable to delete {}", path.display()))
+    }
+
+    fn require(name: &str) -> Result<(), anyhow::Error> {
+        if !Self::exists(name)? {
+            bail!(
+                "Profile {:?} does not exist. Create it with `portform profile new {}`.",
+                name,
+                name
+            );
+        }
+        Ok(())
+    }
+
+    fn require_absent(name: &str) -> Result<(), anyhow::Error> {
+        if Self::exists(name)? {
+            bail!("Profile {:?} already exists", name);
+        }
+        Ok(())
+    }
+
+    // Names become file names, so keep them to a portable character set
+    fn validate_name(name: &str) -> Result<(), anyhow::Error> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+        if !valid {
+            bail!(
+                "Invalid profile name {:?}: use letters, digits, '-' and '_' only",
+                name
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use super::api::{
+    Certifications, ContactDetails, DocumentShape, Education, EmploymentHistory, Projects, SkillSet,
+};
+use super::links::{self, LinkTarget};
+use super::theme::{DateStyle, SectionKind, Theme};
+use super::validation;
+
+/*
+   Format-neutral view of the resume shared by every renderer,
+   so PDF, text and Markdown agree on what each section contains
+*/
+
+// A single entry within a section, e.g. one job or one certification
+#[derive(Debug, Default, Clone)]
+pub struct Entry {
+    pub title: Option<String>,
+    pub meta: Option<String>,
+    pub body: Vec<String>,
+    // Text within the entry that should open a URI when clicked
+    pub links: Vec<LinkTarget>,
+}
+
+impl Entry {
+    // Untitled multi-line entries such as contact details read best as a list
+    pub fn is_list(&self) -> bool {
+        self.title.is_none() && self.body.len() > 1
+    }
+
+    fn is_empty(&self) -> bool {
+        self.title.is_none() && self.meta.is_none() && self.body.iter().all(|b| b.is_empty())
+    }
+}
+
+/// Entries of one section, or None when the section has nothing worth showing
+pub fn entries(kind: SectionKind, document: &DocumentShape, theme: &Theme) -> Option<Vec<Entry>> {
+    let dates = &theme.dates;
+
+    let entries = match kind {
+        SectionKind::Contact => contact(document.contact_details.as_ref()?),
+        SectionKind::Summary => vec![Entry {
+            body: vec![document.summary.as_ref()?.body.clone()],
+            ..Entry::default()
+        }],
+        SectionKind::Employment => employment(document.employment_history.clone()?, dates),
+        SectionKind::Education => education(document.education.clone()?, dates),
+        SectionKind::Projects => projects(document.projects.clone()?),
+        SectionKind::Skills => skills(document.skillset.clone()?),
+        SectionKind::Certifications => certifications(document.certifications.clone()?, dates),
+    };
+
+    let entries: Vec<Entry> = entries.into_iter().filter(|e| !e.is_empty()).collect();
+
+    Some(entries).filter(|entries| !entries.is_empty())
+}
+
+/// Every visible section in reading order: the sidebar first, then the main column
+pub fn visible(theme: &Theme, document: &DocumentShape) -> Vec<(SectionKind, Vec<Entry>)> {
+    theme
+        .sidebar()
+        .iter()
+        .chain(theme.sections.iter())
+        .filter_map(|&kind| entries(kind, document, theme).map(|entries| (kind, entries)))
+        .collect()
+}
+
+fn contact(contact: &ContactDetails) -> Vec<Entry> {
+    let ContactDetails {
+        email,
+        website,
+        phone,
+        address,
+    } = contact.clone();
+
+    let links = [
+        (email.clone(), Some(links::mailto(&email))),
+        (website.clone(), links::web(&website)),
+        (validation::display_phone(&phone), Some(links::tel(&phone))),
+    ]
+    .into_iter()
+    .filter(|(text, _)| !text.trim().is_empty())
+    .filter_map(|(text, uri)| uri.map(|uri| (text, uri)))
+    .collect();
+
+    let body = [
+        ("EMAIL", email),
+        ("PORTFOLIO", website),
+        ("PHONE #", validation::display_phone(&phone)),
+        ("LOCATION", address),
+    ]
+    .into_iter()
+    .filter(|(_, value)| !value.is_empty())
+    .map(|(label, value)| format!("{}: {}", label, value))
+    .collect();
+
+    vec![Entry {
+        body,
+        links,
+        ..Entry::default()
+    }]
+}
+
+fn employment(history: EmploymentHistory, dates: &DateStyle) -> Vec<Entry> {
+    history
+        .into_iter()
+        .map(|entry| Entry {
+            title: Some(format!("{}  |  {}", entry.position, entry.location)),
+            meta: entry.dates_employed.format(dates),
+            body: vec![entry.description],
+            links: Vec::new(),
+        })
+        .collect()
+}
+
+fn education(education: Education, dates: &DateStyle) -> Vec<Entry> {
+    education
+        .into_iter()
+        .map(|entry| Entry {
+            title: Some(format!("{}  |  {}", entry.name, entry.location)),
+            meta: entry.dates.format(dates),
+            body: vec![entry.description],
+            links: Vec::new(),
+        })
+        .collect()
+}
+
+fn projects(projects: Projects) -> Vec<Entry> {
+    projects
+        .into_iter()
+        .map(|project| Entry {
+            title: Some(project.name),
+            links: Some(&project.deployment)
+                .and_then(|d| links::web(d).map(|uri| (d.clone(), uri)))
+                .into_iter()
+                .collect(),
+            meta: Some(project.deployment).filter(|d| !d.is_empty()),
+            body: vec![project.description],
+        })
+        .collect()
+}
+
+fn skills(skillset: SkillSet) -> Vec<Entry> {
+    let names: Vec<String> = skillset.into_iter().map(|skill| skill.name).collect();
+
+    vec![Entry {
+        body: vec![names.join(", ")],
+        ..Entry::default()
+    }]
+}
+
+fn certifications(certifications: Certifications, dates: &DateStyle) -> Vec<Entry> {
+    certifications
+        .into_iter()
+        .map(|cert| Entry {
+            title: Some(cert.name),
+            meta: cert.date_issued.map(|date| date.format(dates)),
+            body: Vec::new(),
+            links: Vec::new(),
+        })
+        .collect()
+}
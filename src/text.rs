@@ -0,0 +1,236 @@
+use super::api::DocumentShape;
+use super::layout::Layout;
+use super::links;
+use super::sections::{self, Entry};
+use super::theme::{Align, Theme};
+
+// Paste-able renderings of the resume for job portals
+#[derive(Debug, Clone, Copy)]
+pub enum TextFormat {
+    // Fixed-width text wrapped at the given column
+    Plain { columns: usize },
+    // GitHub-flavored Markdown
+    Markdown,
+}
+
+impl TextFormat {
+    /*
+       Render the header and every visible section,
+       in the same order and with the same titles as the PDF
+    */
+    pub fn render(self, document: &DocumentShape, theme: &Theme) -> String {
+        let mut blocks = Vec::new();
+
+        if let Some(header) = &document.header {
+            blocks.push(match self {
+                TextFormat::Plain { columns } => [&header.name, &header.profession]
+                    .into_iter()
+                    .filter(|line| !line.is_empty())
+                    .flat_map(|line| Layout::wrap_columns(line, columns))
+                    .map(|line| match theme.header.align {
+                        Align::Center => center(&line, columns),
+                        Align::Left => line,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                TextFormat::Markdown => {
+                    format!(
+                        "# {}\n\n{}",
+                        escape(&header.name),
+                        escape(&header.profession)
+                    )
+                }
+            });
+        }
+
+        for (kind, entries) in sections::visible(theme, document) {
+            let title = theme.section_title(kind);
+
+            let heading = match self {
+                TextFormat::Plain { .. } => {
+                    format!("{}\n{}", title, "-".repeat(title.chars().count()))
+                }
+                TextFormat::Markdown => format!("## {}", escape(&title)),
+            };
+
+            let entries: Vec<String> = entries.iter().map(|entry| self.entry(entry)).collect();
+            blocks.push(format!("{}\n\n{}", heading, entries.join("\n\n")));
+        }
+
+        let mut text = blocks.join("\n\n");
+        text.push('\n');
+        text
+    }
+
+    fn entry(self, entry: &Entry) -> String {
+        match self {
+            TextFormat::Plain { columns } => entry
+                .title
+                .iter()
+                .chain(entry.meta.iter())
+                .chain(entry.body.iter().filter(|body| !body.is_empty()))
+                .flat_map(|text| Layout::wrap_columns(text, columns))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            TextFormat::Markdown => {
+                let mut lines = Vec::new();
+
+                if let Some(title) = &entry.title {
+                    lines.push(format!("### {}", linked(title, &entry.links)));
+                }
+
+                if let Some(meta) = &entry.meta {
+                    lines.push(format!("*{}*", linked(meta, &entry.links)));
+                }
+
+                let body = entry.body.iter().filter(|body| !body.is_empty());
+
+                if entry.is_list() {
+                    let items: Vec<String> = body
+                        .map(|line| format!("- {}", linked(line.trim(), &entry.links)))
+                        .collect();
+                    lines.push(items.join("\n"));
+                } else {
+                    lines.extend(body.map(|text| {
+                        text.lines()
+                            .map(|line| linked(line.trim(), &entry.links))
+                            .collect::<Vec<_>>()
+                            // Trailing double space is a hard line break
+                            .join("  \n")
+                    }));
+                }
+
+                lines.join("\n\n")
+            }
+        }
+    }
+}
+
+fn center(line: &str, columns: usize) -> String {
+    let padding = columns.saturating_sub(line.chars().count()) / 2;
+    format!("{}{}", " ".repeat(padding), line)
+}
+
+// Escape link text and turn it into inline Markdown links
+fn linked(text: &str, targets: &[links::LinkTarget]) -> String {
+    links::spans(text, targets)
+        .into_iter()
+        .map(|(span, uri)| match uri {
+            Some(uri) => format!("[{}]({})", escape(span), uri.replace(' ', "%20")),
+            None => escape(span),
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    let escaped = text
+        .chars()
+        .fold(String::with_capacity(text.len()), |mut out, c| {
+            if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>') {
+                out.push('\\');
+            }
+            out.push(c);
+            out
+        });
+
+    // A leading # would turn the line into a heading
+    if escaped.starts_with('#') {
+        format!("\\{}", escaped)
+    } else {
+        escaped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{ContactDetails, Header, Project, Summary};
+
+    fn document() -> DocumentShape {
+        DocumentShape {
+            header: Some(Header {
+                name: "Ada Lovelace".to_string(),
+                profession: "Analyst".to_string(),
+            }),
+            summary: Some(Summary {
+                body: "Wrote the first program\nfor the Analytical Engine".to_string(),
+            }),
+            contact_details: Some(ContactDetails {
+                email: "ada@example.com".to_string(),
+                address: "London".to_string(),
+                ..ContactDetails::default()
+            }),
+            projects: Some(vec![Project {
+                name: "Notes".to_string(),
+                description: "Translation with notes".to_string(),
+                deployment: "example.com".to_string(),
+            }]),
+            ..DocumentShape::default()
+        }
+    }
+
+    #[test]
+    fn wraps_plain_text_at_the_column_limit() {
+        let text = TextFormat::Plain { columns: 16 }.render(&document(), &Theme::default());
+
+        assert!(
+            text.lines().all(|line| line.chars().count() <= 16),
+            "{}",
+            text
+        );
+        assert!(text.contains("Wrote the first\nprogram\nfor the\nAnalytical\nEngine"));
+    }
+
+    #[test]
+    fn centers_the_plain_header() {
+        let mut theme = Theme::default();
+        theme.header.align = Align::Center;
+
+        let text = TextFormat::Plain { columns: 20 }.render(&document(), &theme);
+        assert!(
+            text.starts_with("    Ada Lovelace\n      Analyst\n"),
+            "{}",
+            text
+        );
+
+        theme.header.align = Align::Left;
+        let text = TextFormat::Plain { columns: 20 }.render(&document(), &theme);
+        assert!(text.starts_with("Ada Lovelace\nAnalyst\n"), "{}", text);
+    }
+
+    #[test]
+    fn escapes_markdown_syntax() {
+        assert_eq!(escape("*a* _b_ [c] #1"), "\\*a\\* \\_b\\_ \\[c\\] #1");
+        assert_eq!(escape("#1 seller"), "\\#1 seller");
+        assert_eq!(escape("C# and F#"), "C# and F#");
+    }
+
+    #[test]
+    fn renders_untitled_entries_as_lists_and_the_rest_as_paragraphs() {
+        let markdown = TextFormat::Markdown.render(&document(), &Theme::default());
+
+        assert!(
+            markdown.starts_with("# Ada Lovelace\n\nAnalyst\n\n"),
+            "{}",
+            markdown
+        );
+        assert!(markdown
+            .contains("- EMAIL: [ada@example.com](mailto:ada@example.com)\n- LOCATION: London"));
+        assert!(markdown.contains("Wrote the first program  \nfor the Analytical Engine"));
+        assert!(!markdown.contains("- Wrote"));
+    }
+
+    #[test]
+    fn links_text_inline() {
+        let markdown = TextFormat::Markdown.render(&document(), &Theme::default());
+
+        assert!(markdown.contains("### Notes\n\n*[example.com](https://example.com)*"));
+        assert_eq!(
+            linked(
+                "see my_site",
+                &[("my_site".to_string(), "https://a.b/c d".to_string())]
+            ),
+            "see [my\\_site](https://a.b/c%20d)"
+        );
+    }
+}
@@ -0,0 +1,793 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+use chrono::format::{Item, StrftimeItems};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::consts;
+use super::errors::PortformError;
+use super::fonts::BaseFont;
+use super::layout::{Margins, TextStyle};
+
+// Every section that can be placed on the page, in the order written by default
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionKind {
+    Contact,
+    Summary,
+    Employment,
+    Education,
+    Projects,
+    Skills,
+    Certifications,
+}
+
+impl SectionKind {
+    pub const ALL: [SectionKind; 7] = [
+        SectionKind::Contact,
+        SectionKind::Summary,
+        SectionKind::Employment,
+        SectionKind::Education,
+        SectionKind::Projects,
+        SectionKind::Skills,
+        SectionKind::Certifications,
+    ];
+
+    // Name used for the section in theme files and on the command line
+    pub fn key(&self) -> &'static str {
+        match self {
+            SectionKind::Contact => "contact",
+            SectionKind::Summary => "summary",
+            SectionKind::Employment => "employment",
+            SectionKind::Education => "education",
+            SectionKind::Projects => "projects",
+            SectionKind::Skills => "skills",
+            SectionKind::Certifications => "certifications",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            SectionKind::Contact => "Contact Details",
+            SectionKind::Summary => "Summary",
+            SectionKind::Employment => "Employment History",
+            SectionKind::Education => "Education",
+            SectionKind::Projects => "Projects",
+            SectionKind::Skills => "Skills",
+            SectionKind::Certifications => "Certifications",
+        }
+    }
+}
+
+// 8-bit RGB color written as "#rrggbb" in theme definitions
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub struct ThemeColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl ThemeColor {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    pub fn to_pdf(self) -> printpdf::Color {
+        let channel = |c: u8| c as f64 / 255.0;
+        printpdf::Color::Rgb(printpdf::Rgb::new(
+            channel(self.r),
+            channel(self.g),
+            channel(self.b),
+            None,
+        ))
+    }
+}
+
+impl TryFrom<String> for ThemeColor {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, anyhow::Error> {
+        let hex = value.trim().trim_start_matches('#');
+
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            bail!("invalid color {:?}, expected \"#rrggbb\"", value);
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+
+        Ok(Self::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+impl From<ThemeColor> for String {
+    fn from(value: ThemeColor) -> Self {
+        format!("#{:02x}{:02x}{:02x}", value.r, value.g, value.b)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Palette {
+    pub text: ThemeColor,
+    pub heading: ThemeColor,
+    pub accent: ThemeColor,
+    pub muted: ThemeColor,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Align {
+    Left,
+    Center,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HeaderStyle {
+    pub name: TextStyle,
+    pub profession: TextStyle,
+    pub align: Align,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HeadingStyle {
+    pub text: TextStyle,
+    pub uppercase: bool,
+    pub bold: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DividerKind {
+    None,
+    Rule,
+    Bar,
+}
+
+// Line drawn under each section heading
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DividerStyle {
+    pub kind: DividerKind,
+    pub thickness: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ColumnLayout {
+    Single,
+    TwoColumn {
+        sidebar_width: f64,
+        gutter: f64,
+        sidebar: Vec<SectionKind>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sidebar_fill: Option<ThemeColor>,
+    },
+}
+
+// How clickable email, phone and web links are set apart from surrounding text
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(deny_unknown_fields)]
+pub struct LinkStyle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<ThemeColor>,
+    #[serde(default)]
+    pub underline: bool,
+}
+
+// How dates are written, with chrono strftime patterns for month and day precision
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct DateStyle {
+    pub month: String,
+    pub day: String,
+    pub present: String,
+    pub separator: String,
+    // Append how long each range lasted, e.g. "2 yrs 4 mos"
+    pub durations: bool,
+}
+
+impl Default for DateStyle {
+    fn default() -> Self {
+        Self {
+            month: "%b %Y".to_string(),
+            day: "%b %-d, %Y".to_string(),
+            present: "Present".to_string(),
+            separator: " \u{2013} ".to_string(),
+            durations: false,
+        }
+    }
+}
+
+// Physical page dimensions in mm
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct PageSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Default for PageSize {
+    fn default() -> Self {
+        Self {
+            width: consts::PAGE_X,
+            height: consts::PAGE_Y,
+        }
+    }
+}
+
+// Overrides applied to a single section on top of the theme-wide styles
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SectionStyle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading_color: Option<ThemeColor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<TextStyle>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Theme {
+    pub name: String,
+    pub page: PageSize,
+    pub margins: Margins,
+    pub colors: Palette,
+    pub header: HeaderStyle,
+    pub heading: HeadingStyle,
+    pub divider: DividerStyle,
+    pub body: TextStyle,
+    pub footer: TextStyle,
+    pub section_gap: f64,
+    pub entry_gap: f64,
+    pub layout: ColumnLayout,
+    pub sections: Vec<SectionKind>,
+    #[serde(default)]
+    pub section_styles: BTreeMap<SectionKind, SectionStyle>,
+    // Write with a base-14 font instead of the configured or embedded one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font: Option<BaseFont>,
+    #[serde(default)]
+    pub links: LinkStyle,
+    #[serde(default)]
+    pub dates: DateStyle,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl Theme {
+    pub const BUILTIN: [&'static str; 3] = ["classic", "modern", "compact"];
+
+    pub fn builtin(name: &str) -> Result<Self, anyhow::Error> {
+        match name.to_ascii_lowercase().as_str() {
+            "classic" => Ok(Self::classic()),
+            "modern" => Ok(Self::modern()),
+            "compact" => Ok(Self::compact()),
+            _ => Err(PortformError::Validation {
+                field: "theme".to_string(),
+                value: Some(name.to_string()),
+                message: format!("available themes are {}", Self::BUILTIN.join(", ")),
+            })?,
+        }
+    }
+
+    /*
+       Load a theme definition from a .toml or .json file.
+       Keys left out are taken from the built-in theme named by `extends` (classic by default),
+       and errors name the offending key.
+    */
+    pub fn from_file(path: &Path) -> Result<Self, anyhow::Error> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read theme file {}", path.display()))?;
+
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+
+        let mut overrides: Value = match extension.to_ascii_lowercase().as_str() {
+            "toml" => toml::from_str(&text)
+                .map_err(|e| anyhow!("{}: invalid TOML: {}", path.display(), e))?,
+            "json" => serde_json::from_str(&text)
+                .map_err(|e| anyhow!("{}: invalid JSON: {}", path.display(), e))?,
+            _ => bail!(
+                "Unsupported theme file {}: expected a .toml or .json extension",
+                path.display()
+            ),
+        };
+
+        let Some(fields) = overrides.as_object_mut() else {
+            bail!("{}: a theme must be a table of keys", path.display());
+        };
+
+        let base = match fields.remove("extends") {
+            None => Self::default(),
+            Some(Value::String(name)) => {
+                Self::builtin(&name).map_err(|e| anyhow!("{}: `extends`: {}", path.display(), e))?
+            }
+            Some(_) => bail!("{}: `extends` must be a theme name", path.display()),
+        };
+
+        let mut merged = serde_json::to_value(base)?;
+        merge(&mut merged, overrides);
+
+        let theme: Theme = serde_path_to_error::deserialize(merged).map_err(|e| {
+            anyhow!(
+                "{}: invalid value for `{}`: {}",
+                path.display(),
+                e.path(),
+                e.inner()
+            )
+        })?;
+
+        theme
+            .validate()
+            .map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+
+        Ok(theme)
+    }
+
+    pub fn to_toml(&self) -> Result<String, anyhow::Error> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, anyhow::Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    // Checks that deserialization alone cannot express
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        let positive = |key: &str, value: f64| {
+            if value.is_finite() && value > 0.0 {
+                Ok(())
+            } else {
+                Err(anyhow!(
+                    "`{}` must be greater than zero, found {}",
+                    key,
+                    value
+                ))
+            }
+        };
+        let non_negative = |key: &str, value: f64| {
+            if value.is_finite() && value >= 0.0 {
+                Ok(())
+            } else {
+                Err(anyhow!("`{}` must not be negative, found {}", key, value))
+            }
+        };
+
+        positive("page.width", self.page.width)?;
+        positive("page.height", self.page.height)?;
+
+        let margins = [
+            ("margins.top", self.margins.top),
+            ("margins.right", self.margins.right),
+            ("margins.bottom", self.margins.bottom),
+            ("margins.left", self.margins.left),
+        ];
+        for (key, value) in margins {
+            non_negative(key, value)?;
+        }
+
+        if self.margins.content_width(self.page.width) < 20.0 {
+            bail!("`margins.left` and `margins.right` leave no room on the page");
+        }
+        if self.page.height - self.margins.top - self.margins.bottom < 20.0 {
+            bail!("`margins.top` and `margins.bottom` leave no room on the page");
+        }
+
+        let mut styles = vec![
+            ("header.name", self.header.name),
+            ("header.profession", self.header.profession),
+            ("heading.text", self.heading.text),
+            ("body", self.body),
+            ("footer", self.footer),
+        ];
+        let section_bodies: Vec<(String, TextStyle)> = self
+            .section_styles
+            .iter()
+            .filter_map(|(kind, style)| {
+                let key = format!("section_styles.{}.body", kind.key());
+                style.body.map(|body| (key, body))
+            })
+            .collect();
+        styles.extend(
+            section_bodies
+                .iter()
+                .map(|(key, style)| (key.as_str(), *style)),
+        );
+
+        for (key, style) in styles {
+            positive(&format!("{}.size", key), style.size)?;
+            positive(&format!("{}.line_height", key), style.line_height)?;
+            non_negative(&format!("{}.char_spacing", key), style.char_spacing)?;
+        }
+
+        non_negative("section_gap", self.section_gap)?;
+        non_negative("entry_gap", self.entry_gap)?;
+        non_negative("divider.thickness", self.divider.thickness)?;
+
+        if let ColumnLayout::TwoColumn {
+            sidebar_width,
+            gutter,
+            ..
+        } = self.layout
+        {
+            positive("layout.sidebar_width", sidebar_width)?;
+            non_negative("layout.gutter", gutter)?;
+
+            if sidebar_width + gutter > self.margins.content_width(self.page.width) - 20.0 {
+                bail!(
+                    "`layout.sidebar_width` and `layout.gutter` leave no room for the main column"
+                );
+            }
+        }
+
+        // chrono only reports a bad pattern when formatting, so check it up front
+        for (key, pattern) in [
+            ("dates.month", &self.dates.month),
+            ("dates.day", &self.dates.day),
+        ] {
+            if StrftimeItems::new(pattern).any(|item| item == Item::Error) {
+                bail!("`{}` is not a valid date pattern: {:?}", key, pattern);
+            }
+        }
+
+        let mut placed: Vec<SectionKind> = Vec::with_capacity(SectionKind::ALL.len());
+        for kind in self.sidebar().iter().chain(self.sections.iter()) {
+            if placed.contains(kind) {
+                bail!(
+                    "section {:?} is listed more than once across `sections` and `layout.sidebar`",
+                    kind.key()
+                );
+            }
+            placed.push(*kind);
+        }
+
+        Ok(())
+    }
+
+    // Title printed above a section, honoring per-section overrides
+    pub fn section_title(&self, kind: SectionKind) -> String {
+        let title = self
+            .section_styles
+            .get(&kind)
+            .and_then(|style| style.title.clone())
+            .unwrap_or_else(|| kind.title().to_string());
+
+        if self.heading.uppercase {
+            title.to_uppercase()
+        } else {
+            title
+        }
+    }
+
+    // Sections that belong in the sidebar of a two-column layout
+    pub fn sidebar(&self) -> &[SectionKind] {
+        match &self.layout {
+            ColumnLayout::Single => &[],
+            ColumnLayout::TwoColumn { sidebar, .. } => sidebar,
+        }
+    }
+
+    pub fn classic() -> Self {
+        Self {
+            name: "classic".to_string(),
+            page: PageSize::default(),
+            margins: Margins::default(),
+            colors: Palette {
+                text: ThemeColor::new(0x00, 0x00, 0x00),
+                heading: ThemeColor::new(0x00, 0x00, 0x00),
+                accent: ThemeColor::new(0x00, 0x00, 0xc8),
+                muted: ThemeColor::new(0x55, 0x55, 0x55),
+            },
+            header: HeaderStyle {
+                name: TextStyle {
+                    size: 24.0,
+                    char_spacing: 3.0,
+                    line_height: 20.0,
+                },
+                profession: TextStyle {
+                    size: 13.0,
+                    char_spacing: 3.0,
+                    line_height: 22.0,
+                },
+                align: Align::Left,
+            },
+            heading: HeadingStyle {
+                text: TextStyle {
+                    size: 10.0,
+                    char_spacing: 1.0,
+                    line_height: consts::BODY_LINE_HEIGHT,
+                },
+                uppercase: true,
+                bold: true,
+            },
+            divider: DividerStyle {
+                kind: DividerKind::Rule,
+                thickness: 0.5,
+            },
+            body: TextStyle {
+                size: 9.0,
+                char_spacing: 1.0,
+                line_height: consts::BODY_LINE_HEIGHT,
+            },
+            footer: TextStyle {
+                size: 8.0,
+                char_spacing: 0.5,
+                line_height: 10.0,
+            },
+            section_gap: consts::SECTION_GAP,
+            entry_gap: consts::ENTRY_GAP,
+            layout: ColumnLayout::Single,
+            sections: SectionKind::ALL.to_vec(),
+            section_styles: BTreeMap::new(),
+            font: None,
+            links: LinkStyle::default(),
+            dates: DateStyle::default(),
+        }
+    }
+
+    pub fn modern() -> Self {
+        Self {
+            name: "modern".to_string(),
+            page: PageSize::default(),
+            margins: Margins {
+                top: 14.0,
+                right: 12.0,
+                bottom: 15.0,
+                left: 12.0,
+            },
+            colors: Palette {
+                text: ThemeColor::new(0x22, 0x22, 0x22),
+                heading: ThemeColor::new(0x0b, 0x6e, 0x4f),
+                accent: ThemeColor::new(0x0b, 0x6e, 0x4f),
+                muted: ThemeColor::new(0x6b, 0x6b, 0x6b),
+            },
+            header: HeaderStyle {
+                name: TextStyle {
+                    size: 26.0,
+                    char_spacing: 1.5,
+                    line_height: 26.0,
+                },
+                profession: TextStyle {
+                    size: 12.0,
+                    char_spacing: 1.0,
+                    line_height: 22.0,
+                },
+                align: Align::Left,
+            },
+            heading: HeadingStyle {
+                text: TextStyle {
+                    size: 10.0,
+                    char_spacing: 1.5,
+                    line_height: 13.0,
+                },
+                uppercase: true,
+                bold: true,
+            },
+            divider: DividerStyle {
+                kind: DividerKind::Bar,
+                thickness: 1.2,
+            },
+            body: TextStyle {
+                size: 8.5,
+                char_spacing: 0.3,
+                line_height: 11.0,
+            },
+            footer: TextStyle {
+                size: 7.5,
+                char_spacing: 0.3,
+                line_height: 10.0,
+            },
+            section_gap: 7.0,
+            entry_gap: 2.5,
+            layout: ColumnLayout::TwoColumn {
+                sidebar_width: 58.0,
+                gutter: 8.0,
+                sidebar: vec![
+                    SectionKind::Contact,
+                    SectionKind::Skills,
+                    SectionKind::Certifications,
+                    SectionKind::Education,
+                ],
+                sidebar_fill: Some(ThemeColor::new(0xee, 0xf4, 0xf1)),
+            },
+            sections: vec![
+                SectionKind::Summary,
+                SectionKind::Employment,
+                SectionKind::Projects,
+            ],
+            section_styles: BTreeMap::new(),
+            font: None,
+            links: LinkStyle {
+                color: Some(ThemeColor::new(0x0b, 0x6e, 0x4f)),
+                underline: false,
+            },
+            dates: DateStyle {
+                durations: true,
+                ..DateStyle::default()
+            },
+        }
+    }
+
+    pub fn compact() -> Self {
+        Self {
+            name: "compact".to_string(),
+            page: PageSize::default(),
+            margins: Margins {
+                top: 8.0,
+                right: 8.0,
+                bottom: 10.0,
+                left: 8.0,
+            },
+            colors: Palette {
+                text: ThemeColor::new(0x00, 0x00, 0x00),
+                heading: ThemeColor::new(0x00, 0x00, 0x00),
+                accent: ThemeColor::new(0x44, 0x44, 0x44),
+                muted: ThemeColor::new(0x66, 0x66, 0x66),
+            },
+            header: HeaderStyle {
+                name: TextStyle {
+                    size: 18.0,
+                    char_spacing: 1.0,
+                    line_height: 18.0,
+                },
+                profession: TextStyle {
+                    size: 11.0,
+                    char_spacing: 1.0,
+                    line_height: 15.0,
+                },
+                align: Align::Center,
+            },
+            heading: HeadingStyle {
+                text: TextStyle {
+                    size: 9.0,
+                    char_spacing: 0.5,
+                    line_height: 10.0,
+                },
+                uppercase: true,
+                bold: true,
+            },
+            divider: DividerStyle {
+                kind: DividerKind::Rule,
+                thickness: 0.25,
+            },
+            body: TextStyle {
+                size: 8.0,
+                char_spacing: 0.3,
+                line_height: 9.5,
+            },
+            footer: TextStyle {
+                size: 7.0,
+                char_spacing: 0.3,
+                line_height: 8.0,
+            },
+            section_gap: 3.0,
+            entry_gap: 1.0,
+            layout: ColumnLayout::Single,
+            sections: SectionKind::ALL.to_vec(),
+            section_styles: BTreeMap::new(),
+            font: None,
+            links: LinkStyle::default(),
+            dates: DateStyle::default(),
+        }
+    }
+}
+
+/*
+   Overlay `overrides` onto `base` key by key.
+   A table that switches `kind` (e.g. the layout) replaces the base table entirely,
+   since the old variant's keys no longer apply.
+*/
+fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            let kind_changed = overrides
+                .get("kind")
+                .is_some_and(|kind| base.get("kind") != Some(kind));
+
+            if kind_changed {
+                *base = overrides;
+                return;
+            }
+
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Write `contents` to a theme file in a directory of its own, load it and remove the directory
+    fn load(name: &str, contents: &str) -> Result<Theme, anyhow::Error> {
+        let dir =
+            std::env::temp_dir().join(format!("portform-theme-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+
+        let theme = Theme::from_file(&path);
+        std::fs::remove_dir_all(&dir).unwrap();
+        theme
+    }
+
+    #[test]
+    fn merges_a_partial_theme_over_the_default() {
+        let theme = load(
+            "partial.toml",
+            "name = \"mine\"\nsection_gap = 9.0\n\n[body]\nsize = 11.0\n\n[colors]\naccent = \"#112233\"\n",
+        )
+        .unwrap();
+        let classic = Theme::classic();
+
+        assert_eq!(theme.name, "mine");
+        assert_eq!(theme.section_gap, 9.0);
+        assert_eq!(theme.body.size, 11.0);
+        assert_eq!(String::from(theme.colors.accent), "#112233");
+        // Keys left out come from the base, including siblings of overridden ones
+        assert_eq!(theme.body.line_height, classic.body.line_height);
+        assert_eq!(
+            String::from(theme.colors.text),
+            String::from(classic.colors.text)
+        );
+        assert_eq!(theme.entry_gap, classic.entry_gap);
+        assert_eq!(theme.sections, classic.sections);
+    }
+
+    #[test]
+    fn extends_the_named_builtin_theme() {
+        let theme = load(
+            "extends.json",
+            r#"{"extends": "compact", "entry_gap": 1.5}"#,
+        )
+        .unwrap();
+        let compact = Theme::compact();
+
+        assert_eq!(theme.entry_gap, 1.5);
+        assert_eq!(theme.body.size, compact.body.size);
+        assert_eq!(theme.margins.top, compact.margins.top);
+    }
+
+    #[test]
+    fn names_an_unknown_key() {
+        let error = load("unknown.toml", "[body]\nsize = 10.0\nweight = 700\n").unwrap_err();
+        let message = error.to_string();
+
+        assert!(message.contains("`body.weight`"), "{}", message);
+    }
+
+    #[test]
+    fn names_a_key_with_the_wrong_type() {
+        let error = load("type.json", r#"{"margins": {"top": "wide"}}"#).unwrap_err();
+        assert!(error.to_string().contains("`margins.top`"), "{}", error);
+    }
+
+    #[test]
+    fn names_a_key_that_fails_validation() {
+        let error = load("invalid.toml", "[page]\nwidth = -1.0\n").unwrap_err();
+        assert!(error.to_string().contains("`page.width`"), "{}", error);
+
+        let mut theme = Theme::default();
+        theme.dates.month = "%Q".to_string();
+        let error = theme.validate().unwrap_err();
+        assert!(error.to_string().contains("`dates.month`"), "{}", error);
+    }
+}
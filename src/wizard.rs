@@ -0,0 +1,341 @@
+use std::io::IsTerminal;
+
+use anyhow::bail;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Editor, Input, Select};
+
+use super::api::{
+    Certification, ContactDetails, DocumentShape, EducationEntry, Header, HistoryEntry, Labeled,
+    Project, Skill, Summary,
+};
+use super::dates::{DateRange, ResumeDate};
+use super::errors::ValidationError;
+use super::file_io::{ConfigFileHandler, FileHandler};
+use super::os;
+use super::theme::Theme;
+use super::validation;
+
+/*
+   Interactive walk through every section of the config.
+   Current values are pre-filled, and nothing is written until the final confirmation.
+*/
+pub struct Wizard {
+    theme: ColorfulTheme,
+}
+
+impl Wizard {
+    pub fn run() -> Result<(), anyhow::Error> {
+        if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+            bail!("The wizard needs an interactive terminal. Use `portform set` or `portform edit` instead.");
+        }
+
+        let wizard = Self {
+            theme: ColorfulTheme::default(),
+        };
+
+        let config_path = os::resolve_config_path()?.0;
+        let mut document = ConfigFileHandler::read_or_default()?;
+
+        wizard.header(&mut document)?;
+        wizard.contact_details(&mut document)?;
+        wizard.summary(&mut document)?;
+        wizard.list(
+            "Employment history",
+            &mut document.employment_history,
+            |c| wizard.history_entry(c),
+        )?;
+        wizard.list("Projects", &mut document.projects, |c| wizard.project(c))?;
+        wizard.list("Education", &mut document.education, |c| {
+            wizard.education_entry(c)
+        })?;
+        wizard.list("Skills", &mut document.skillset, |c| wizard.skill(c))?;
+        wizard.list("Certifications", &mut document.certifications, |c| {
+            wizard.certification(c)
+        })?;
+        wizard.settings(&mut document)?;
+
+        let save = Confirm::with_theme(&wizard.theme)
+            .with_prompt(format!("Save to {}?", config_path.display()))
+            .default(true)
+            .interact()?;
+
+        if save {
+            ConfigFileHandler::write(document)?;
+            println!("Saved {}", config_path.display());
+        } else {
+            println!("Discarded changes");
+        }
+
+        Ok(())
+    }
+
+    fn header(&self, document: &mut DocumentShape) -> Result<(), anyhow::Error> {
+        self.section("Header");
+        let current = document.header.clone().unwrap_or_default();
+
+        document.header = Some(Header {
+            name: self.text("Full name", &current.name)?,
+            profession: self.text("Profession", &current.profession)?,
+        });
+
+        Ok(())
+    }
+
+    fn contact_details(&self, document: &mut DocumentShape) -> Result<(), anyhow::Error> {
+        self.section("Contact details");
+        let current = document.contact_details.clone().unwrap_or_default();
+
+        document.contact_details = Some(ContactDetails {
+            email: self.checked("Email", &current.email, validation::email)?,
+            website: self.checked("Website", &current.website, validation::website)?,
+            phone: self.checked(
+                "Phone (with country code)",
+                &current.phone,
+                validation::phone,
+            )?,
+            address: self.checked("Address", &current.address, validation::address)?,
+        });
+
+        Ok(())
+    }
+
+    fn summary(&self, document: &mut DocumentShape) -> Result<(), anyhow::Error> {
+        self.section("Summary");
+        let current = document.summary.as_ref().map_or("", |s| s.body.as_str());
+        let body = self.long_text("Summary", current)?;
+
+        document.summary = Some(Summary { body }).filter(|s| !s.body.is_empty());
+
+        Ok(())
+    }
+
+    fn settings(&self, document: &mut DocumentShape) -> Result<(), anyhow::Error> {
+        self.section("Output");
+
+        let filename = self.text("PDF filename", document.filename.as_deref().unwrap_or(""))?;
+        let title = self.text("Document title", document.title.as_deref().unwrap_or(""))?;
+        document.filename = Some(filename).filter(|f| !f.is_empty());
+        document.title = Some(title).filter(|t| !t.is_empty());
+
+        let mut choices = vec!["classic (default)"];
+        choices.extend(&Theme::BUILTIN[1..]);
+
+        let current = document
+            .theme
+            .as_deref()
+            .and_then(|name| {
+                Theme::BUILTIN
+                    .iter()
+                    .position(|t| t.eq_ignore_ascii_case(name))
+            })
+            .unwrap_or(0);
+
+        let choice = Select::with_theme(&self.theme)
+            .with_prompt("Theme")
+            .items(&choices)
+            .default(current)
+            .interact()?;
+
+        document.theme = Some(Theme::BUILTIN[choice].to_string()).filter(|_| choice > 0);
+
+        Ok(())
+    }
+
+    fn history_entry(&self, current: Option<&HistoryEntry>) -> Result<HistoryEntry, anyhow::Error> {
+        Ok(HistoryEntry {
+            position: self.text("Position", current.map_or("", |c| &c.position))?,
+            location: self.text("Location", current.map_or("", |c| &c.location))?,
+            dates_employed: self.dates(current.map(|c| &c.dates_employed))?,
+            description: self.long_text("Description", current.map_or("", |c| &c.description))?,
+            pinned: self.pinned(current.is_some_and(|c| c.pinned))?,
+        })
+    }
+
+    fn project(&self, current: Option<&Project>) -> Result<Project, anyhow::Error> {
+        Ok(Project {
+            name: self.text("Name", current.map_or("", |c| &c.name))?,
+            description: self.long_text("Description", current.map_or("", |c| &c.description))?,
+            deployment: self.text("Deployment URL", current.map_or("", |c| &c.deployment))?,
+        })
+    }
+
+    fn education_entry(
+        &self,
+        current: Option<&EducationEntry>,
+    ) -> Result<EducationEntry, anyhow::Error> {
+        Ok(EducationEntry {
+            name: self.text("School", current.map_or("", |c| &c.name))?,
+            location: self.text("Location", current.map_or("", |c| &c.location))?,
+            dates: self.dates(current.map(|c| &c.dates))?,
+            description: self.long_text("Description", current.map_or("", |c| &c.description))?,
+            pinned: self.pinned(current.is_some_and(|c| c.pinned))?,
+        })
+    }
+
+    fn skill(&self, current: Option<&Skill>) -> Result<Skill, anyhow::Error> {
+        Ok(Skill {
+            name: self.text("Skill", current.map_or("", |c| &c.name))?,
+        })
+    }
+
+    fn certification(
+        &self,
+        current: Option<&Certification>,
+    ) -> Result<Certification, anyhow::Error> {
+        Ok(Certification {
+            name: self.text("Name", current.map_or("", |c| &c.name))?,
+            date_issued: self.date("Issued", current.and_then(|c| c.date_issued.as_ref()))?,
+            pinned: self.pinned(current.is_some_and(|c| c.pinned))?,
+        })
+    }
+
+    // Menu for adding, editing and removing the entries of a list section
+    fn list<T: Labeled>(
+        &self,
+        name: &str,
+        section: &mut Option<Vec<T>>,
+        prompt: impl Fn(Option<&T>) -> Result<T, anyhow::Error>,
+    ) -> Result<(), anyhow::Error> {
+        self.section(name);
+        let mut entries = section.take().unwrap_or_default();
+
+        loop {
+            let mut items: Vec<String> = entries
+                .iter()
+                .map(|entry| format!("Edit \"{}\"", entry.label()))
+                .collect();
+            items.push("Add an entry".to_string());
+            items.push("Continue".to_string());
+
+            let add = entries.len();
+            let choice = Select::with_theme(&self.theme)
+                .with_prompt(name)
+                .items(&items)
+                .default(add)
+                .interact()?;
+
+            if choice == add {
+                entries.push(prompt(None)?);
+            } else if choice < add {
+                let action = Select::with_theme(&self.theme)
+                    .with_prompt(format!("\"{}\"", entries[choice].label()))
+                    .items(&["Edit", "Remove", "Back"])
+                    .default(0)
+                    .interact()?;
+
+                match action {
+                    0 => entries[choice] = prompt(Some(&entries[choice]))?,
+                    1 => {
+                        entries.remove(choice);
+                    }
+                    _ => {}
+                }
+            } else {
+                break;
+            }
+        }
+
+        *section = Some(entries).filter(|entries| !entries.is_empty());
+
+        Ok(())
+    }
+
+    fn section(&self, name: &str) {
+        println!("\n== {} ==", name);
+    }
+
+    fn text(&self, prompt: &str, current: &str) -> Result<String, anyhow::Error> {
+        let value: String = Input::with_theme(&self.theme)
+            .with_prompt(prompt)
+            .with_initial_text(current)
+            .allow_empty(true)
+            .interact_text()?;
+
+        Ok(value.trim().to_string())
+    }
+
+    // Re-prompts until the rule accepts the input, returning the value as the rule stores it
+    fn checked(
+        &self,
+        prompt: &str,
+        current: &str,
+        rule: fn(&str) -> Result<String, ValidationError>,
+    ) -> Result<String, anyhow::Error> {
+        let value: String = Input::with_theme(&self.theme)
+            .with_prompt(prompt)
+            .with_initial_text(current)
+            .allow_empty(true)
+            .validate_with(|input: &String| rule(input).map(|_| ()))
+            .interact_text()?;
+
+        Ok(rule(&value)?)
+    }
+
+    // Asks again until the end date is not before the start date
+    fn dates(&self, current: Option<&DateRange>) -> Result<DateRange, anyhow::Error> {
+        let current = current.cloned().unwrap_or_default();
+
+        loop {
+            let start = self.date("Start", current.start.as_ref())?;
+            let end = self.date("End", current.end.as_ref())?;
+
+            match DateRange::new(start, end) {
+                Ok(range) => return Ok(range),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+    }
+
+    fn date(
+        &self,
+        prompt: &str,
+        current: Option<&ResumeDate>,
+    ) -> Result<Option<ResumeDate>, anyhow::Error> {
+        let current = current.map(ResumeDate::to_string).unwrap_or_default();
+
+        let value: String = Input::with_theme(&self.theme)
+            .with_prompt(format!("{} (e.g. 2021-03, Mar 2021, present)", prompt))
+            .with_initial_text(current)
+            .allow_empty(true)
+            .validate_with(|input: &String| match input.trim() {
+                "" => Ok(()),
+                text => ResumeDate::parse(text).map(|_| ()),
+            })
+            .interact_text()?;
+
+        match value.trim() {
+            "" => Ok(None),
+            text => Ok(Some(ResumeDate::parse(text)?)),
+        }
+    }
+
+    fn pinned(&self, current: bool) -> Result<bool, anyhow::Error> {
+        Ok(Confirm::with_theme(&self.theme)
+            .with_prompt("Pin above the entries sorted by date?")
+            .default(current)
+            .interact()?)
+    }
+
+    // Single-line input cannot hold line breaks, so multi-line values open in the editor
+    fn long_text(&self, prompt: &str, current: &str) -> Result<String, anyhow::Error> {
+        if !current.contains('\n') {
+            return self.text(prompt, current);
+        }
+
+        let edit = Confirm::with_theme(&self.theme)
+            .with_prompt(format!(
+                "{} spans several lines. Edit it in your editor?",
+                prompt
+            ))
+            .default(false)
+            .interact()?;
+
+        if !edit {
+            return Ok(current.to_string());
+        }
+
+        let edited = Editor::new().edit(current)?;
+
+        Ok(edited.map_or(current.to_string(), |text| text.trim_end().to_string()))
+    }
+}